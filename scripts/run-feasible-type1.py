@@ -1,6 +0,0 @@
-#!/usr/bin/python3 -B
-
-from run import *
-import sys
-
-run_all(PROG, FEASIBLE_TYPE1, TIMES)
@@ -1,6 +0,0 @@
-#!/usr/bin/python3 -B
-
-from run import *
-import sys
-
-run_all(PROG, ALL_TYPE1, TIMES)
@@ -0,0 +1,68 @@
+// external
+use fera::graph::prelude::*;
+
+// local
+use {MstCcProblem, Weight};
+
+// The weight of a minimum spanning tree ignoring the conflicts
+pub fn weight_lower_bound<W: Weight>(p: &MstCcProblem<W>) -> W {
+    p.weight(p.mst())
+}
+
+// Every spanning tree contains all bridges, so every conflicting pair of bridges is in every
+// spanning tree
+pub fn conflicts_lower_bound<W>(p: &MstCcProblem<W>) -> u32 {
+    let mut is_bridge = p.g.default_edge_prop(false);
+    is_bridge.set_values(bridges(&p.g), true);
+    let mut num = 0;
+    for e in p.g.edges().filter(|&e| is_bridge[e]) {
+        num += p.cc[e].iter().filter(|&&f| is_bridge[f] && e < f).count();
+    }
+    num as u32
+}
+
+// Tarjan's bridge finding algorithm, with an explicit stack so it works on long paths
+pub fn bridges(g: &StaticGraph) -> Vec<Edge<StaticGraph>> {
+    // discover time, 0 means not discovered
+    let mut discover = g.default_vertex_prop(0u32);
+    let mut low = g.default_vertex_prop(0u32);
+    let mut time = 0;
+    let mut bridges = vec![];
+    let mut stack = vec![];
+
+    for root in g.vertices() {
+        if discover[root] != 0 {
+            continue;
+        }
+        time += 1;
+        discover[root] = time;
+        low[root] = time;
+        stack.push((root, None, g.out_edges(root)));
+
+        while let Some((u, parent, mut edges)) = stack.pop() {
+            if let Some(e) = edges.next() {
+                stack.push((u, parent, edges));
+                if Some(e) == parent {
+                    continue;
+                }
+                let v = g.target(e);
+                if discover[v] == 0 {
+                    time += 1;
+                    discover[v] = time;
+                    low[v] = time;
+                    stack.push((v, Some(e), g.out_edges(v)));
+                } else {
+                    low[u] = low[u].min(discover[v]);
+                }
+            } else if let Some(e) = parent {
+                let p = g.source(e);
+                low[p] = low[p].min(low[u]);
+                if low[u] > discover[p] {
+                    bridges.push(e);
+                }
+            }
+        }
+    }
+
+    bridges
+}
@@ -0,0 +1,123 @@
+// system
+use std::collections::HashSet;
+use std::error::Error;
+
+// external
+use fera::graph::prelude::*;
+
+// local
+use {MstCcProblem, Weight};
+
+// The index of an edge in the order it was added, which is also its index in the graph of the
+// built problem (g.edge_index())
+pub type EdgeId = usize;
+
+// Creates a MstCcProblem in memory. The edges and conflicts are validated in build, so the add
+// methods never fail.
+pub struct MstCcProblemBuilder<W = u32> {
+    name: String,
+    num_vertices: usize,
+    edges: Vec<(usize, usize, W)>,
+    conflicts: Vec<(EdgeId, EdgeId)>,
+    // the conflicts as (min, max), used to ignore the repeated (and symmetric) conflicts
+    pairs: HashSet<(EdgeId, EdgeId)>,
+}
+
+impl<W: Weight> MstCcProblemBuilder<W> {
+    pub fn new(name: &str) -> Self {
+        MstCcProblemBuilder {
+            name: name.into(),
+            num_vertices: 0,
+            edges: vec![],
+            conflicts: vec![],
+            pairs: HashSet::new(),
+        }
+    }
+
+    // Returns the new vertex, the vertices are numbered from 0
+    pub fn add_vertex(&mut self) -> usize {
+        self.num_vertices += 1;
+        self.num_vertices - 1
+    }
+
+    pub fn add_edge(&mut self, u: usize, v: usize, w: W) -> EdgeId {
+        self.edges.push((u, v, w));
+        self.edges.len() - 1
+    }
+
+    // Adds a conflict between e and f, the conflict (f, e) or a repeated conflict is ignored
+    pub fn add_conflict(&mut self, e: EdgeId, f: EdgeId) {
+        if self.pairs.insert((e.min(f), e.max(f))) {
+            self.conflicts.push((e, f));
+        }
+    }
+
+    pub fn num_vertices(&self) -> usize {
+        self.num_vertices
+    }
+
+    pub fn num_edges(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn num_conflicts(&self) -> usize {
+        self.conflicts.len()
+    }
+
+    // Returns the problem (with alpha = 1 and beta = 0, like the reader) or an error if there is a
+    // self-loop, a parallel edge, an invalid weight, a self-conflict or an invalid vertex or edge
+    pub fn build(self) -> Result<MstCcProblem<W>, Box<dyn Error>> {
+        let n = self.num_vertices;
+        let m = self.edges.len();
+        let mut ends = HashSet::new();
+        for (i, &(u, v, w)) in self.edges.iter().enumerate() {
+            if u >= n || v >= n {
+                return Err(format!("edge {}: invalid vertex {}", i, u.max(v)).into());
+            }
+            if u == v {
+                return Err(format!("edge {}: self-loop {}-{}", i, u, v).into());
+            }
+            if !ends.insert((u.min(v), u.max(v))) {
+                return Err(format!("edge {}: parallel edge {}-{}", i, u, v).into());
+            }
+            if !w.is_valid() {
+                return Err(format!("edge {}: invalid weight {}", i, w).into());
+            }
+        }
+        for &(e, f) in &self.conflicts {
+            if e >= m || f >= m {
+                return Err(format!("conflict {}-{}: invalid edge {}", e, f, e.max(f)).into());
+            }
+            if e == f {
+                return Err(format!("conflict {}-{}: self-conflict", e, f).into());
+            }
+        }
+
+        let mut b = StaticGraph::builder(n, m);
+        for &(u, v, _) in &self.edges {
+            b.add_edge(u, v);
+        }
+        let (g, _, edges) = b.finalize_();
+        let mut w = g.default_edge_prop(W::default());
+        for (&e, &(_, _, weight)) in edges.iter().zip(&self.edges) {
+            w[e] = weight;
+        }
+        let mut cc = g.default_edge_prop(vec![]);
+        for &(e, f) in &self.conflicts {
+            cc[edges[e]].push(edges[f]);
+            cc[edges[f]].push(edges[e]);
+        }
+
+        info!("n = {}, m = {}, cc = {}", n, m, self.conflicts.len());
+
+        Ok(MstCcProblem {
+            name: self.name,
+            g,
+            w,
+            cc,
+            num_cc: self.conflicts.len(),
+            alpha: 1.into(),
+            beta: 0.into(),
+        })
+    }
+}
@@ -0,0 +1,23 @@
+// system
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+// A handle used to stop the searches from another thread (or from a signal handler). The clones
+// share the same flag. The searches check the token between iterations and moves and return the
+// best solution found so far.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        CancelToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,140 @@
+// system
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Write};
+use std::str::FromStr;
+
+// external
+use fera::fun::vec;
+use fera::graph::prelude::*;
+
+// local
+use {RngVersion, StreamRng};
+
+// The saved state of a search, used to resume it. It is written as lines "key value", where a
+// list value is separated by spaces and the edges are written as their indices in the graph.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Checkpoint {
+    entries: Vec<(String, String)>,
+}
+
+impl Checkpoint {
+    pub fn new() -> Self {
+        Checkpoint::default()
+    }
+
+    // Sets the value of key, replacing the previous value
+    pub fn set<T: Display>(&mut self, key: &str, value: T) {
+        let value = value.to_string();
+        assert!(!value.contains('\n'), "{}: the value has a new line", key);
+        match self.entries.iter_mut().find(|e| e.0 == key) {
+            Some(entry) => entry.1 = value,
+            None => self.entries.push((key.into(), value)),
+        }
+    }
+
+    pub fn set_list<T: Display>(&mut self, key: &str, values: &[T]) {
+        let values: Vec<_> = values.iter().map(ToString::to_string).collect();
+        self.set(key, values.join(" "));
+    }
+
+    pub fn set_edges(&mut self, g: &StaticGraph, key: &str, edges: &[Edge<StaticGraph>]) {
+        let index = g.edge_index();
+        let edges: Vec<_> = edges.iter().map(|&e| index.get(e)).collect();
+        self.set_list(key, &edges);
+    }
+
+    // Sets the state of rng, which must not be a legacy stream
+    pub fn set_rng(&mut self, key: &str, rng: &StreamRng) {
+        let (state, inc) = rng
+            .state()
+            .expect("the state of a legacy rng can not be saved");
+        self.set(key, format!("{} {} {}", rng.version().value(), state, inc));
+    }
+
+    pub fn get<T: FromStr>(&self, key: &str) -> Result<T, String> {
+        let value = self.value(key)?;
+        value
+            .parse()
+            .map_err(|_| format!("{}: invalid value {}", key, value))
+    }
+
+    pub fn get_list<T: FromStr>(&self, key: &str) -> Result<Vec<T>, String> {
+        self.value(key)?
+            .split_whitespace()
+            .map(|v| {
+                v.parse()
+                    .map_err(|_| format!("{}: invalid value {}", key, v))
+            })
+            .collect()
+    }
+
+    pub fn get_edges(&self, g: &StaticGraph, key: &str) -> Result<Vec<Edge<StaticGraph>>, String> {
+        let edges = vec(g.edges());
+        self.get_list::<usize>(key)?
+            .into_iter()
+            .map(|i| {
+                edges
+                    .get(i)
+                    .cloned()
+                    .ok_or_else(|| format!("{}: invalid edge {}", key, i))
+            })
+            .collect()
+    }
+
+    pub fn get_rng(&self, key: &str) -> Result<StreamRng, String> {
+        let invalid = || format!("{}: invalid rng state", key);
+        match *self.get_list::<u64>(key)? {
+            [version, state, inc] => match version.to_string().parse() {
+                Ok(RngVersion::V0) | Err(_) => Err(invalid()),
+                Ok(version) => Ok(StreamRng::from_state(version, state, inc)),
+            },
+            _ => Err(invalid()),
+        }
+    }
+
+    fn value(&self, key: &str) -> Result<&str, String> {
+        self.entries
+            .iter()
+            .find(|e| e.0 == key)
+            .map(|e| e.1.as_str())
+            .ok_or_else(|| format!("missing {}", key))
+    }
+
+    // Writes the checkpoint to a temporary file that is renamed to file, so an interrupted write
+    // keeps the previous checkpoint
+    pub fn write(&self, file: &str) -> Result<(), Box<dyn Error>> {
+        let tmp = format!("{}.tmp", file);
+        {
+            let mut out = File::create(&tmp)?;
+            write!(out, "{}", self)?;
+            out.sync_all()?;
+        }
+        fs::rename(&tmp, file)?;
+        Ok(())
+    }
+
+    pub fn read(file: &str) -> Result<Checkpoint, Box<dyn Error>> {
+        let mut c = Checkpoint::new();
+        for line in BufReader::new(File::open(file)?).lines() {
+            let line = line?;
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut s = line.splitn(2, ' ');
+            let key = s.next().unwrap();
+            c.set(key, s.next().unwrap_or(""));
+        }
+        Ok(c)
+    }
+}
+
+impl fmt::Display for Checkpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (key, value) in &self.entries {
+            writeln!(f, "{} {}", key, value)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,262 @@
+// system
+use std::error::Error;
+use std::fs::File;
+use std::io::Read;
+
+// local
+use Json;
+
+// An experiment is a set of named algorithm configurations that are run on named sets of
+// instances. It is written in a subset of toml:
+//
+// ```toml
+// repetitions = 30
+//
+// [instances.type2]
+// files = ["../data/type2"]
+//
+// [config.B1]
+// init = "random"
+// alg = "ils-2ex"
+// ils-max-iters = "m"
+// ils-excludes = 3
+// instances = ["type2"]
+// ```
+//
+// The top level keys are the settings of the experiment (seed, repetitions and threads). The
+// keys of a configuration are the long options of the solve subcommand (init and alg included).
+// A configuration without instances runs on all instance sets.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Experiment {
+    // top level keys
+    pub settings: Vec<(String, Json)>,
+    pub instances: Vec<(String, Vec<String>)>,
+    pub configs: Vec<AlgorithmConfig>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlgorithmConfig {
+    pub name: String,
+    pub params: Vec<(String, Json)>,
+    pub instances: Vec<String>,
+}
+
+impl Experiment {
+    pub fn setting(&self, key: &str) -> Option<&Json> {
+        self.settings.iter().find(|s| s.0 == key).map(|s| &s.1)
+    }
+
+    pub fn instance_set(&self, name: &str) -> Option<&[String]> {
+        self.instances.iter().find(|s| s.0 == name).map(|s| &*s.1)
+    }
+}
+
+// The valid top level keys
+const SETTINGS: [&str; 3] = ["seed", "repetitions", "threads"];
+
+pub fn read_experiment(file: &str) -> Result<Experiment, Box<dyn Error>> {
+    let mut s = String::new();
+    File::open(file)?.read_to_string(&mut s)?;
+    parse_experiment(&s).map_err(|e| format!("{}: {}", file, e).into())
+}
+
+pub fn parse_experiment(s: &str) -> Result<Experiment, String> {
+    let mut experiment = Experiment {
+        settings: vec![],
+        instances: vec![],
+        configs: vec![],
+    };
+    for (section, fields) in parse_toml(s)? {
+        match section {
+            None => {
+                if let Some(f) = fields.iter().find(|f| !SETTINGS.contains(&f.0.as_str())) {
+                    return Err(format!("invalid key {}", f.0));
+                }
+                experiment.settings = fields;
+            }
+            Some((ref kind, name)) if kind == "instances" => {
+                let mut files = vec![];
+                for (key, value) in fields {
+                    if key != "files" {
+                        return Err(format!("instances.{}: invalid key {}", name, key));
+                    }
+                    files = strings(&value).ok_or_else(|| {
+                        format!("instances.{}: files must be an array of strings", name)
+                    })?;
+                }
+                experiment.instances.push((name, files));
+            }
+            Some((ref kind, name)) if kind == "config" => {
+                let mut config = AlgorithmConfig {
+                    name,
+                    params: vec![],
+                    instances: vec![],
+                };
+                for (key, value) in fields {
+                    if key == "instances" {
+                        config.instances = strings(&value).ok_or_else(|| {
+                            format!(
+                                "config.{}: instances must be an array of strings",
+                                config.name
+                            )
+                        })?;
+                    } else {
+                        config.params.push((key.replace('_', "-"), value));
+                    }
+                }
+                experiment.configs.push(config);
+            }
+            Some((kind, name)) => return Err(format!("invalid section [{}.{}]", kind, name)),
+        }
+    }
+
+    for config in &experiment.configs {
+        for name in &config.instances {
+            if experiment.instance_set(name).is_none() {
+                return Err(format!(
+                    "config.{}: undefined instance set {}",
+                    config.name, name
+                ));
+            }
+        }
+    }
+
+    Ok(experiment)
+}
+
+fn strings(value: &Json) -> Option<Vec<String>> {
+    if let Json::Array(ref values) = *value {
+        values
+            .iter()
+            .map(|v| match *v {
+                Json::Str(ref s) => Some(s.clone()),
+                _ => None,
+            })
+            .collect()
+    } else {
+        None
+    }
+}
+
+type Section = (Option<(String, String)>, Vec<(String, Json)>);
+
+// Parses the supported subset of toml: [kind.name] sections, key = value pairs, with strings,
+// integers, floats, booleans and (possibly multiline) arrays as values and # comments.
+fn parse_toml(s: &str) -> Result<Vec<Section>, String> {
+    let mut sections: Vec<Section> = vec![(None, vec![])];
+    let mut lines = s.lines().enumerate();
+    while let Some((i, line)) = lines.next() {
+        let mut line = strip_comment(line).trim().to_string();
+        if line.is_empty() {
+            continue;
+        }
+        let error = |msg: &str| format!("line {}: {}", i + 1, msg);
+
+        if line.starts_with('[') {
+            if !line.ends_with(']') {
+                return Err(error("invalid section"));
+            }
+            let name = &line[1..line.len() - 1];
+            let mut parts = name.splitn(2, '.');
+            match (parts.next(), parts.next()) {
+                (Some(kind), Some(name)) if is_key(kind) && is_key(name) => {
+                    sections.push((Some((kind.into(), name.into())), vec![]));
+                }
+                _ => return Err(error("section must be [kind.name]")),
+            }
+            continue;
+        }
+
+        // arrays may span multiple lines
+        while line.matches('[').count() > line.matches(']').count() {
+            match lines.next() {
+                Some((_, next)) => {
+                    line.push(' ');
+                    line.push_str(strip_comment(next).trim());
+                }
+                None => return Err(error("unterminated array")),
+            }
+        }
+
+        let mut parts = line.splitn(2, '=');
+        let (key, value) = match (parts.next(), parts.next()) {
+            (Some(key), Some(value)) => (key.trim(), value.trim()),
+            _ => return Err(error("expected key = value")),
+        };
+        if !is_key(key) {
+            return Err(error(&format!("invalid key {}", key)));
+        }
+        let (value, rest) = parse_value(value).map_err(|e| error(&e))?;
+        if !rest.trim().is_empty() {
+            return Err(error(&format!("unexpected {}", rest.trim())));
+        }
+        let fields = &mut sections.last_mut().unwrap().1;
+        if fields.iter().any(|f| f.0 == key) {
+            return Err(error(&format!("duplicated key {}", key)));
+        }
+        fields.push((key.into(), value));
+    }
+    Ok(sections)
+}
+
+fn is_key(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_str = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_str = !in_str,
+            '#' if !in_str => return &line[..i],
+            _ => (),
+        }
+    }
+    line
+}
+
+// Parses a value in the start of s and returns the value and the rest of s
+fn parse_value(s: &str) -> Result<(Json, &str), String> {
+    let s = s.trim_start();
+    if let Some(s) = s.strip_prefix('"') {
+        let end = s
+            .find('"')
+            .ok_or_else(|| "unterminated string".to_string())?;
+        Ok((Json::Str(s[..end].into()), &s[end + 1..]))
+    } else if let Some(s) = s.strip_prefix('[') {
+        let mut values = vec![];
+        let mut rest = s.trim_start();
+        while !rest.starts_with(']') {
+            let (value, r) = parse_value(rest)?;
+            values.push(value);
+            rest = r.trim_start();
+            if let Some(r) = rest.strip_prefix(',') {
+                rest = r.trim_start();
+            } else if !rest.starts_with(']') {
+                return Err("expected , or ] in array".into());
+            }
+        }
+        Ok((Json::Array(values), &rest[1..]))
+    } else {
+        let end = s
+            .find(|c: char| c == ',' || c == ']' || c.is_whitespace())
+            .unwrap_or(s.len());
+        let (token, rest) = s.split_at(end);
+        let value = match token {
+            "true" => Json::Bool(true),
+            "false" => Json::Bool(false),
+            _ => {
+                if let Ok(i) = token.parse() {
+                    Json::Int(i)
+                } else if let Ok(x) = token.parse() {
+                    Json::Float(x)
+                } else {
+                    return Err(format!("invalid value {}", token));
+                }
+            }
+        };
+        Ok((value, rest))
+    }
+}
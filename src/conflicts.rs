@@ -9,10 +9,12 @@ use fera::graph::prelude::*;
 use MstCcProblem;
 
 // TODO: replace with OptionMax
-const NONE: usize = ::std::usize::MAX;
+const NONE: usize = usize::MAX;
 
 pub struct TrackConflicts<'a> {
-    p: &'a MstCcProblem, // TODO: only need p.cc and p.g
+    g: &'a StaticGraph,
+    // the edges that conflicts with each edge (p.cc)
+    conflicting: &'a DefaultEdgePropMut<StaticGraph, Vec<Edge<StaticGraph>>>,
     edges: Vec<Edge<StaticGraph>>,
     // position of e in edges or NONE if e is not in edges
     pos: DefaultEdgePropMut<StaticGraph, usize>,
@@ -22,17 +24,25 @@ pub struct TrackConflicts<'a> {
 }
 
 impl<'a> TrackConflicts<'a> {
-    pub fn new(p: &'a MstCcProblem) -> Self {
+    pub fn new<W>(p: &'a MstCcProblem<W>) -> Self {
+        Self::from_parts(&p.g, &p.cc)
+    }
+
+    fn from_parts(
+        g: &'a StaticGraph,
+        conflicting: &'a DefaultEdgePropMut<StaticGraph, Vec<Edge<StaticGraph>>>,
+    ) -> Self {
         TrackConflicts {
-            p,
+            g,
+            conflicting,
             edges: vec![],
-            pos: p.g.default_edge_prop(NONE),
-            cc: p.g.default_edge_prop(0),
+            pos: g.default_edge_prop(NONE),
+            cc: g.default_edge_prop(0),
             conflicts: 0,
         }
     }
 
-    pub fn with_edges<I>(p: &'a MstCcProblem, edges: I) -> Self
+    pub fn with_edges<W, I>(p: &'a MstCcProblem<W>, edges: I) -> Self
     where
         I: IntoIterator,
         I::Item: IntoOwned<Edge<StaticGraph>>,
@@ -45,8 +55,8 @@ impl<'a> TrackConflicts<'a> {
     pub fn reset(&mut self) {
         self.edges.clear();
         self.conflicts = 0;
-        self.pos.set_values(self.p.g.edges(), NONE);
-        self.cc.set_values(self.p.g.edges(), 0);
+        self.pos.set_values(self.g.edges(), NONE);
+        self.cc.set_values(self.g.edges(), 0);
     }
 
     pub fn replace(&mut self, rem: Edge<StaticGraph>, add: Edge<StaticGraph>) {
@@ -62,7 +72,7 @@ impl<'a> TrackConflicts<'a> {
             self.pos[self.edges[p]] = p;
         }
         self.pos[rem] = NONE;
-        for &e in &self.p.cc[rem] {
+        for &e in &self.conflicting[rem] {
             self.cc[e] -= 1;
             if self.pos[e] != NONE {
                 self.conflicts -= 1;
@@ -85,7 +95,7 @@ impl<'a> TrackConflicts<'a> {
         assert!(p == NONE);
         self.edges.push(add);
         self.pos[add] = self.edges.len() - 1;
-        for &e in &self.p.cc[add] {
+        for &e in &self.conflicting[add] {
             self.cc[e] += 1;
             if self.pos[e] != NONE {
                 self.conflicts += 1;
@@ -105,10 +115,26 @@ impl<'a> TrackConflicts<'a> {
         self.conflicts
     }
 
+    // Each conflicting pair of edges is reported once, as (e, f) with index(e) < index(f)
+    pub fn pairs(&self) -> Vec<(Edge<StaticGraph>, Edge<StaticGraph>)> {
+        let index = self.g.edge_index();
+        let mut pairs = vec![];
+        for &e in &self.edges {
+            for &f in &self.conflicting[e] {
+                if self.contains(f) && index.get(e) < index.get(f) {
+                    pairs.push((e, f));
+                }
+            }
+        }
+        pairs.sort_by_key(|&(e, f)| (index.get(e), index.get(f)));
+        pairs
+    }
+
     pub fn check(&self) {
-        let new = Self::with_edges(self.p, &self.edges);
+        let mut new = Self::from_parts(self.g, self.conflicting);
+        new.add_edges(&self.edges);
         assert_eq!(new.total(), self.total());
-        for e in self.p.g.edges() {
+        for e in self.g.edges() {
             assert_eq!(new.contains(e), self.contains(e));
             assert_eq!(new[e], self[e]);
         }
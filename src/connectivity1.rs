@@ -3,14 +3,13 @@ use fera::fun::first;
 use fera::graph::algs::Trees;
 use fera::graph::params::IntoOwned;
 use fera::graph::prelude::*;
-use fera::graph::props::{Color, IgnoreWriteProp};
-use fera::graph::traverse::{OnDiscoverVertex, OnFinishVertex, RecursiveDfs, StampTime, Time};
 
 pub struct TrackConnectivity1<'a, G: 'a + IncidenceGraph> {
     g: SpanningSubgraph<'a, G>,
-    // TODO: change to u32
-    discover: DefaultVertexPropMut<SpanningSubgraph<'a, G>, u64>,
-    finish: DefaultVertexPropMut<SpanningSubgraph<'a, G>, u64>,
+    discover: DefaultVertexPropMut<SpanningSubgraph<'a, G>, u32>,
+    finish: DefaultVertexPropMut<SpanningSubgraph<'a, G>, u32>,
+    // the stack of tree_dfs_times, kept to reuse its allocation
+    stack: Vec<(Vertex<G>, Vertex<G>, bool)>,
     root: Vertex<G>,
     sub: Vertex<G>,
 }
@@ -25,6 +24,7 @@ impl<'a, G: 'a + IncidenceGraph> TrackConnectivity1<'a, G> {
             g,
             discover,
             finish,
+            stack: vec![],
             root,
             sub: root,
         }
@@ -33,16 +33,13 @@ impl<'a, G: 'a + IncidenceGraph> TrackConnectivity1<'a, G> {
     fn dfs(&mut self) {
         // TODO: is_tree is allocating!
         assert!(self.g.is_tree());
-        let time = Time::default();
-        // g is a tree (acyclic and connected), so we can ignore the color changes
-        self.g
-            .recursive_dfs((
-                OnDiscoverVertex(StampTime(&time, &mut self.discover)),
-                OnFinishVertex(StampTime(&time, &mut self.finish)),
-            ))
-            .root(self.root)
-            .color(&mut IgnoreWriteProp::new_vertex_prop(&self.g, Color::White))
-            .run();
+        tree_dfs_times(
+            &self.g,
+            self.root,
+            &mut self.discover,
+            &mut self.finish,
+            &mut self.stack,
+        );
     }
 
     #[inline]
@@ -77,3 +74,40 @@ impl<'a, G: 'a + IncidenceGraph> TrackConnectivity1<'a, G> {
         self.dfs()
     }
 }
+
+// Sets the discover and finish times of a dfs from root on the tree g. g is a tree (acyclic and
+// connected), so the only neighbor of a vertex that was already discovered is its parent and the
+// colors are not needed. The stack, which has the vertices to discover (with their parents) or to
+// finish, is explicit because a recursive dfs overflows the thread stack when the tree is a long
+// path.
+pub fn tree_dfs_times<G, P>(
+    g: &G,
+    root: Vertex<G>,
+    discover: &mut P,
+    finish: &mut P,
+    stack: &mut Vec<(Vertex<G>, Vertex<G>, bool)>,
+) where
+    G: AdjacencyGraph,
+    P: VertexPropMut<G, u32>,
+{
+    let mut time = 0;
+    stack.clear();
+    stack.push((root, root, false));
+    while let Some((u, parent, finished)) = stack.pop() {
+        if finished {
+            finish[u] = time;
+        } else {
+            discover[u] = time;
+            stack.push((u, parent, true));
+            // the children are reversed to be discovered in the order of out_neighbors
+            let start = stack.len();
+            stack.extend(
+                g.out_neighbors(u)
+                    .filter(|&v| v != parent)
+                    .map(|v| (v, u, false)),
+            );
+            stack[start..].reverse();
+        }
+        time += 1;
+    }
+}
@@ -3,14 +3,16 @@ use fera::fun::first;
 use fera::graph::algs::Trees;
 use fera::graph::params::IntoOwned;
 use fera::graph::prelude::*;
-use fera::graph::props::{Color, IgnoreWriteProp};
-use fera::graph::traverse::{OnDiscoverVertex, OnFinishVertex, RecursiveDfs, StampTime, Time};
+
+// local
+use tree_dfs_times;
 
 pub struct TrackConnectivity2<'a, G: 'a + IncidenceGraph> {
     g: SpanningSubgraph<'a, G>,
-    // TODO: change to u32
-    discover: DefaultVertexPropMut<SpanningSubgraph<'a, G>, u64>,
-    finish: DefaultVertexPropMut<SpanningSubgraph<'a, G>, u64>,
+    discover: DefaultVertexPropMut<SpanningSubgraph<'a, G>, u32>,
+    finish: DefaultVertexPropMut<SpanningSubgraph<'a, G>, u32>,
+    // the stack of tree_dfs_times, kept to reuse its allocation
+    stack: Vec<(Vertex<G>, Vertex<G>, bool)>,
     root: Vertex<G>,
     sub_a: Vertex<G>,
     sub_b: Vertex<G>,
@@ -26,6 +28,7 @@ impl<'a, G: 'a + IncidenceGraph> TrackConnectivity2<'a, G> {
             g,
             discover,
             finish,
+            stack: vec![],
             root,
             sub_a: root,
             sub_b: root,
@@ -35,16 +38,13 @@ impl<'a, G: 'a + IncidenceGraph> TrackConnectivity2<'a, G> {
     fn dfs(&mut self) {
         // FIXME: is_tree is allocating!
         assert!(self.g.is_tree());
-        let time = Time::default();
-        // g is a tree (acyclic and connected), so we can ignore the color changes
-        self.g
-            .recursive_dfs((
-                OnDiscoverVertex(StampTime(&time, &mut self.discover)),
-                OnFinishVertex(StampTime(&time, &mut self.finish)),
-            ))
-            .root(self.root)
-            .color(&mut IgnoreWriteProp::new_vertex_prop(&self.g, Color::White))
-            .run();
+        tree_dfs_times(
+            &self.g,
+            self.root,
+            &mut self.discover,
+            &mut self.finish,
+            &mut self.stack,
+        );
     }
 
     #[inline]
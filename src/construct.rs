@@ -1,19 +1,27 @@
+// system
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::str::FromStr;
+
 // external
 use fera::fun::vec;
 use fera::graph::prelude::*;
-use fera::graph::props::FnProp;
 use fera::graph::unionfind::WithUnionFind;
 
 // local
-use {MstCcProblem, TrackConflicts};
+use {sort_by_weight, MstCcProblem, StreamRng, TrackConflicts, Weight};
 
-pub fn new_greedy(p: &MstCcProblem, tree: &mut Vec<Edge<StaticGraph>>) {
+// Note that the first pass of the inner loop adds all the edges that do not create a cycle, so
+// the conflicts are never used to sort the edges. It is kept as is to reproduce the previous
+// results, new_prim_greedy takes the conflicts into account.
+pub fn new_greedy<W: Weight>(p: &MstCcProblem<W>, tree: &mut Vec<Edge<StaticGraph>>) {
     let mut edges = vec(p.g.edges());
-    let mut conflicts = TrackConflicts::new(&p);
+    let mut conflicts = TrackConflicts::new(p);
     let mut ds = p.g.new_unionfind();
     let mut start = 0;
     while ds.num_sets() > 1 {
-        edges[start..].sort_by_prop(FnProp(|e| p.obj(p.w.get(e), conflicts[e])));
+        sort_by_weight(&mut edges[start..], |&e| p.obj(p.w[e], conflicts[e]));
         for (i, &e) in edges[start..].iter().enumerate() {
             let (u, v) = p.g.ends(e);
             if ds.in_same_set(u, v) {
@@ -26,3 +34,163 @@ pub fn new_greedy(p: &MstCcProblem, tree: &mut Vec<Edge<StaticGraph>>) {
         }
     }
 }
+
+// Grows the tree from a root (one for each component) always adding the edge leaving the tree
+// with the smallest p.obj(w, conflicts). As the conflicts of an edge only increase when the tree
+// grows, the keys in the queue are lower bounds, so an outdated edge is reinserted when it is
+// removed from the queue.
+pub fn new_prim_greedy<W: Weight>(p: &MstCcProblem<W>, tree: &mut Vec<Edge<StaticGraph>>) {
+    let g = &p.g;
+    let edges = vec(g.edges());
+    let index = g.edge_index();
+    let cost = |conflicts: &TrackConflicts, e| p.obj(p.w.get(e), conflicts[e]);
+    let mut conflicts = TrackConflicts::new(p);
+    let mut in_tree = g.default_vertex_prop(false);
+    let mut queue = BinaryHeap::new();
+    for root in g.vertices() {
+        if in_tree[root] {
+            continue;
+        }
+        let mut next = Some(root);
+        while let Some(u) = next.take() {
+            in_tree[u] = true;
+            for e in g.out_edges(u) {
+                if !in_tree[g.target(e)] {
+                    queue.push(Item(cost(&conflicts, e), index.get(e)));
+                }
+            }
+            while let Some(Item(c, i)) = queue.pop() {
+                let e = edges[i];
+                let (a, b) = g.ends(e);
+                if in_tree[a] && in_tree[b] {
+                    continue;
+                }
+                let new = cost(&conflicts, e);
+                if new != c {
+                    queue.push(Item(new, i));
+                    continue;
+                }
+                conflicts.add_edge(e);
+                tree.push(e);
+                next = Some(if in_tree[a] { b } else { a });
+                break;
+            }
+        }
+    }
+}
+
+// An item of the queue of new_prim_greedy, ordered so the item with the smallest (cost, index) is
+// the greatest
+struct Item<W>(W, usize);
+
+impl<W: Weight> Ord for Item<W> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .0
+            .cmp_weight(&self.0)
+            .then_with(|| other.1.cmp(&self.1))
+    }
+}
+
+impl<W: Weight> PartialOrd for Item<W> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<W: Weight> PartialEq for Item<W> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<W: Weight> Eq for Item<W> {}
+
+// The restricted candidate list of the grasp construction
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Rcl {
+    // the alpha * number of candidates edges with the smallest cost (at least one)
+    Cardinality(f64),
+    // the edges with cost at most min + alpha * (max - min)
+    Value(f64),
+}
+
+// Randomized version of new_greedy: in each step the edge is chosen at random from the
+// restricted candidate list
+pub fn new_grasp<W: Weight>(
+    p: &MstCcProblem<W>,
+    tree: &mut Vec<Edge<StaticGraph>>,
+    rcl: Rcl,
+    rng: &mut StreamRng,
+) {
+    let mut edges = vec(p.g.edges());
+    let mut conflicts = TrackConflicts::new(p);
+    let mut ds = p.g.new_unionfind();
+    let mut costs = vec![];
+    let mut candidates = vec![];
+    for _ in 1..p.g.num_vertices() {
+        edges.retain(|&e| {
+            let (u, v) = p.g.ends(e);
+            !ds.in_same_set(u, v)
+        });
+        if edges.is_empty() {
+            break;
+        }
+        costs.clear();
+        costs.extend(edges.iter().map(|&e| p.obj(p.w.get(e), conflicts[e])));
+        candidates.clear();
+        candidates.extend(0..edges.len());
+        match rcl {
+            Rcl::Cardinality(alpha) => {
+                let k = (alpha * edges.len() as f64).ceil() as usize;
+                sort_by_weight(&mut candidates, |&i| costs[i]);
+                candidates.truncate(k.max(1));
+            }
+            Rcl::Value(alpha) => {
+                let min = costs
+                    .iter()
+                    .fold(costs[0], |a, &b| if b < a { b } else { a });
+                let max = costs
+                    .iter()
+                    .fold(costs[0], |a, &b| if b > a { b } else { a });
+                let limit = min.to_f64() + alpha * (max - min).to_f64();
+                candidates.retain(|&i| costs[i].to_f64() <= limit);
+            }
+        }
+        let e = edges[candidates[rng.gen_index(candidates.len())]];
+        let (u, v) = p.g.ends(e);
+        ds.union(u, v);
+        conflicts.add_edge(e);
+        tree.push(e);
+    }
+}
+
+// The format of the grasp-rcl option: cardinality:alpha or value:alpha
+impl FromStr for Rcl {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("invalid rcl {}", s);
+        let mut parts = s.splitn(2, ':');
+        let kind = parts.next().unwrap();
+        let alpha = parts
+            .next()
+            .and_then(|alpha| alpha.parse::<f64>().ok())
+            .filter(|alpha| (0.0..=1.0).contains(alpha))
+            .ok_or_else(error)?;
+        match kind {
+            "cardinality" => Ok(Rcl::Cardinality(alpha)),
+            "value" => Ok(Rcl::Value(alpha)),
+            _ => Err(error()),
+        }
+    }
+}
+
+impl fmt::Display for Rcl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Rcl::Cardinality(alpha) => write!(f, "cardinality:{}", alpha),
+            Rcl::Value(alpha) => write!(f, "value:{}", alpha),
+        }
+    }
+}
@@ -0,0 +1,102 @@
+// system
+use std::collections::HashSet;
+
+// external
+use fera::graph::prelude::*;
+
+// local
+use {MstCcProblem, StreamRng};
+
+// Checks the arguments of new_random_instance
+pub fn check_random_instance(
+    n: usize,
+    m: usize,
+    c: usize,
+    (min_weight, max_weight): (u32, u32),
+) -> Result<(), String> {
+    if n == 0 {
+        return Err("n must be greater than 0".into());
+    }
+    if m < n - 1 || m > n * (n - 1) / 2 {
+        return Err("m must be in [n - 1, n (n - 1) / 2]".into());
+    }
+    if c > m * m.saturating_sub(1) / 2 {
+        return Err("c must be at most m (m - 1) / 2".into());
+    }
+    if min_weight > max_weight {
+        return Err("invalid weight range".into());
+    }
+    Ok(())
+}
+
+// Creates a random connected instance with n vertices, m edges, c conflicting pairs of edges and
+// weights uniformly distributed in [min_weight, max_weight].
+pub fn new_random_instance(
+    name: &str,
+    n: usize,
+    m: usize,
+    c: usize,
+    (min_weight, max_weight): (u32, u32),
+    rng: &mut StreamRng,
+) -> MstCcProblem {
+    if let Err(e) = check_random_instance(n, m, c, (min_weight, max_weight)) {
+        panic!("{}", e);
+    }
+
+    let mut ends = HashSet::new();
+    let mut edges = vec![];
+    let mut add = |u: u32, v: u32, edges: &mut Vec<_>| {
+        let (u, v) = if u < v { (u, v) } else { (v, u) };
+        if ends.insert((u, v)) {
+            edges.push((u, v));
+        }
+    };
+
+    // a random spanning tree makes the graph connected
+    let mut vertices: Vec<u32> = (0..n as u32).collect();
+    rng.shuffle(&mut vertices);
+    for i in 1..n {
+        let j = rng.gen_index(i);
+        add(vertices[i], vertices[j], &mut edges);
+    }
+
+    while edges.len() < m {
+        let u = rng.gen_index(n) as u32;
+        let v = rng.gen_index(n) as u32;
+        if u != v {
+            add(u, v, &mut edges);
+        }
+    }
+
+    let mut b = StaticGraph::builder(n, m);
+    for &(u, v) in &edges {
+        b.add_edge(u as usize, v as usize);
+    }
+    let (g, _, e) = b.finalize_();
+
+    let weights: Vec<u32> = (0..m)
+        .map(|_| min_weight + rng.gen_index((max_weight - min_weight) as usize + 1) as u32)
+        .collect();
+    let w = g.default_edge_prop_from_fn(|f| weights[g.edge_index().get(f)]);
+
+    let mut cc = g.default_edge_prop(vec![]);
+    let mut pairs = HashSet::new();
+    while pairs.len() < c {
+        let i = rng.gen_index(m);
+        let j = rng.gen_index(m);
+        if i != j && pairs.insert((i.min(j), i.max(j))) {
+            cc[e[i]].push(e[j]);
+            cc[e[j]].push(e[i]);
+        }
+    }
+
+    MstCcProblem {
+        name: name.into(),
+        g,
+        w,
+        cc,
+        num_cc: c,
+        alpha: 1.into(),
+        beta: 0.into(),
+    }
+}
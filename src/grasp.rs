@@ -0,0 +1,69 @@
+// external
+use fera::graph::prelude::*;
+use fera::graph::sum_prop;
+
+// local
+use {CancelToken, Cost, MstCcProblem, Observer, StreamRng, Weight};
+
+// Repeats a randomized construction followed by a local search and keeps the best solution. The
+// first iteration starts from the tree given to run.
+pub struct Grasp<'a, W: 'a, C> {
+    pub p: &'a MstCcProblem<W>,
+    pub max_iters: u32,
+    pub construct: C,
+    pub stop_on_feasible: bool,
+    pub observer: &'a dyn Observer<W>,
+    pub cancel: CancelToken,
+}
+
+impl<'a, W: Weight, C> Grasp<'a, W, C> {
+    pub fn run<F>(
+        &mut self,
+        tree: &mut Vec<Edge<StaticGraph>>,
+        rng: &mut StreamRng,
+        mut local_search: F,
+    ) -> u32
+    where
+        F: FnMut(&mut Vec<Edge<StaticGraph>>) -> u32,
+        C: FnMut(&mut Vec<Edge<StaticGraph>>, &mut StreamRng),
+    {
+        let w = &self.p.w;
+        let mut best = tree.clone();
+        let mut best_weight: W = sum_prop(w, &*tree);
+        let mut best_num_conflicts = u32::MAX;
+        let mut best_obj = W::max_value();
+
+        for iter in 0..self.max_iters.max(1) {
+            if iter != 0 {
+                tree.clear();
+                (self.construct)(tree, rng);
+            }
+            let num_conflicts = local_search(tree);
+            let weight: W = sum_prop(w, &*tree);
+            let obj = self.p.obj(weight, num_conflicts);
+            let cost = Cost {
+                num_conflicts,
+                weight,
+            };
+            self.observer.on_iteration("grasp", iter, cost);
+            if obj.improves(best_obj) {
+                let old = Cost {
+                    num_conflicts: best_num_conflicts,
+                    weight: best_weight,
+                };
+                self.observer.on_improvement("grasp", iter, old, cost);
+                best_num_conflicts = num_conflicts;
+                best_weight = weight;
+                best_obj = obj;
+                best.clone_from(&*tree);
+            }
+            if (self.stop_on_feasible && num_conflicts == 0) || self.cancel.is_cancelled() {
+                break;
+            }
+        }
+
+        tree.clone_from(&best);
+
+        best_num_conflicts
+    }
+}
@@ -1,121 +1,382 @@
 // system
-use std::mem;
+use std::fmt;
+use std::str::FromStr;
 
 // external
-use fera::fun::vec;
-use fera::graph::algs::Kruskal;
 use fera::graph::prelude::*;
 use fera::graph::sum_prop;
-use rand::{Rng, XorShiftRng};
 
 // local
-use MstCcProblem;
+use {
+    CancelToken, Checkpoint, Cost, LogObserver, MstCcProblem, Observer, Perturbation, StreamRng,
+    Weight,
+};
 
-pub struct Ils<'a, R> {
-    pub p: &'a MstCcProblem,
+pub struct Ils<'a, W: 'a, R, P> {
+    pub p: &'a MstCcProblem<W>,
     pub max_iters: u32,
     pub max_iters_no_improv: u32,
     pub num_excludes: u32,
     pub iters_restart: u32,
     pub iters_restart_to_best: u32,
     pub restart: R,
+    pub perturbation: P,
+    pub acceptance: Acceptance,
     pub stop_on_feasible: bool,
+    pub observer: &'a dyn Observer<W>,
+    pub cancel: CancelToken,
+    pub checkpoint: Option<IlsCheckpoint<'a>>,
 }
 
-impl<'a, R> Ils<'a, R> {
+// Saves the state of the ils at the start of every `every` iterations
+pub struct IlsCheckpoint<'a> {
+    pub every: u32,
+    // receives the state of the ils, its rng and its perturbation and adds the state that is not
+    // owned by the ils (like the state of the restart) before writing it
+    pub save: Box<dyn FnMut(&mut Checkpoint) + 'a>,
+}
+
+impl<'a, W: Weight, R, P> Ils<'a, W, R, P> {
+    // An ils with the defaults of the solver (1000 iterations, 1 excluded edge, no restarts and
+    // random walk acceptance), the other parameters are set in the fields
+    pub fn new(p: &'a MstCcProblem<W>, restart: R, perturbation: P) -> Self {
+        Ils {
+            p,
+            max_iters: 1000,
+            max_iters_no_improv: 1_000_000_000,
+            num_excludes: 1,
+            iters_restart: 1_000_000_000,
+            iters_restart_to_best: 1_000_000_000,
+            restart,
+            perturbation,
+            acceptance: Acceptance::RandomWalk,
+            stop_on_feasible: false,
+            observer: &LogObserver,
+            cancel: CancelToken::new(),
+            checkpoint: None,
+        }
+    }
+
     #[inline(never)]
     pub fn run<F>(
         &mut self,
         tree: &mut Vec<Edge<StaticGraph>>,
-        rng: &mut XorShiftRng,
-        mut local_search: F,
+        rng: &mut StreamRng,
+        local_search: F,
     ) -> u32
     where
         F: FnMut(&mut Vec<Edge<StaticGraph>>) -> u32,
         R: FnMut(&mut Vec<Edge<StaticGraph>>),
+        P: Perturbation,
     {
-        let (g, w) = (&self.p.g, &self.p.w);
-        let mut edges = vec(g.edges());
-        let mut best = tree.clone();
-        let mut best_weight = sum_prop(w, &*tree);
-        let mut best_num_conflicts = u32::max_value();
-        let mut best_obj = u32::max_value();
+        let state = IlsState::new(self.p, tree);
+        self.perturbation.start();
+        self.run_from(state, tree, rng, local_search)
+    }
 
-        let mut iters_no_impr = 0;
-        let mut iters_restart = 0;
-        let mut iters_restart_to_best = 0;
+    // Continues the run saved in c, the tree, the rng and the state of the perturbation are
+    // replaced by the saved ones. The result is the same of the run that saved c.
+    pub fn resume<F>(
+        &mut self,
+        c: &Checkpoint,
+        tree: &mut Vec<Edge<StaticGraph>>,
+        rng: &mut StreamRng,
+        local_search: F,
+    ) -> Result<u32, String>
+    where
+        F: FnMut(&mut Vec<Edge<StaticGraph>>) -> u32,
+        R: FnMut(&mut Vec<Edge<StaticGraph>>),
+        P: Perturbation,
+    {
+        let state = IlsState::load(&self.p.g, c)?;
+        *tree = c.get_edges(&self.p.g, "tree")?;
+        *rng = c.get_rng("rng")?;
+        self.perturbation.start();
+        self.perturbation.restore(c)?;
+        info!("Resuming the ils from iteration {}", state.iter);
+        Ok(self.run_from(state, tree, rng, local_search))
+    }
 
-        let mut tmp = vec![];
-        let mut exclude = vec![];
+    fn run_from<F>(
+        &mut self,
+        mut s: IlsState<W>,
+        tree: &mut Vec<Edge<StaticGraph>>,
+        rng: &mut StreamRng,
+        mut local_search: F,
+    ) -> u32
+    where
+        F: FnMut(&mut Vec<Edge<StaticGraph>>) -> u32,
+        R: FnMut(&mut Vec<Edge<StaticGraph>>),
+        P: Perturbation,
+    {
+        let g = &self.p.g;
+        let w = &self.p.w;
+        let start = s.iter;
+
+        for iter in start..self.max_iters {
+            if let Some(ref mut checkpoint) = self.checkpoint {
+                if iter != start && checkpoint.every != 0 && iter % checkpoint.every == 0 {
+                    s.iter = iter;
+                    let mut c = Checkpoint::new();
+                    s.save(g, &mut c);
+                    c.set_edges(g, "tree", tree);
+                    c.set_rng("rng", rng);
+                    self.perturbation.save(&mut c);
+                    (checkpoint.save)(&mut c);
+                }
+            }
 
-        for iter in 0..self.max_iters {
             let num_conflicts = local_search(tree);
-            let weight = sum_prop(w, &*tree);
-            let obj = self.p.obj(weight, num_conflicts);
-            if obj < best_obj {
-                info!("ils - iter      {}", iter);
-                info!(
-                    "ils - conflicts {} -> {}",
-                    best_num_conflicts, num_conflicts
-                );
-                info!("ils - weight    {} -> {}", best_weight, weight);
-                best_num_conflicts = num_conflicts;
-                best_weight = weight;
-                best_obj = obj;
-                best.clone_from(&*tree);
-                iters_no_impr = 0;
-                iters_restart = 0;
-                iters_restart_to_best = 0;
+            let weight: W = sum_prop(w, &*tree);
+            let mut obj = self.p.obj(weight, num_conflicts);
+            let mut accepted = self.acceptance.accept(
+                &mut s.acceptance,
+                obj,
+                s.current_obj,
+                s.best_obj,
+                iter,
+                rng,
+            ) || s.accept_next;
+            s.accept_next = false;
+            let cost = Cost {
+                num_conflicts,
+                weight,
+            };
+            self.observer.on_iteration("ils", iter, cost);
+            if obj.improves(s.best_obj) {
+                let old = Cost {
+                    num_conflicts: s.best_num_conflicts,
+                    weight: s.best_weight,
+                };
+                self.observer.on_improvement("ils", iter, old, cost);
+                s.best_num_conflicts = num_conflicts;
+                s.best_weight = weight;
+                s.best_obj = obj;
+                s.best.clone_from(&*tree);
+                s.iters_no_impr = 0;
+                s.iters_restart = 0;
+                s.iters_restart_to_best = 0;
             } else {
-                iters_no_impr += 1;
-                if iters_no_impr >= self.max_iters_no_improv {
+                s.iters_no_impr += 1;
+                if s.iters_no_impr >= self.max_iters_no_improv {
                     break;
                 }
 
-                iters_restart += 1;
-                if iters_restart >= self.iters_restart {
-                    info!("ils - restart");
+                s.iters_restart += 1;
+                if s.iters_restart >= self.iters_restart {
+                    self.observer.on_restart("ils", iter, false);
                     tree.clear();
                     (self.restart)(tree);
-                    iters_restart = 0;
+                    s.iters_restart = 0;
+                    s.accept_next = true;
                     continue;
                 }
 
-                iters_restart_to_best += 1;
-                if iters_restart_to_best >= self.iters_restart_to_best {
-                    info!("ils - restart to best");
-                    tree.clone_from(&best);
-                    iters_restart_to_best = 0;
+                s.iters_restart_to_best += 1;
+                if s.iters_restart_to_best >= self.iters_restart_to_best {
+                    self.observer.on_restart("ils", iter, true);
+                    tree.clone_from(&s.best);
+                    obj = s.best_obj;
+                    accepted = true;
+                    s.iters_restart_to_best = 0;
                 }
             }
 
-            if self.stop_on_feasible && num_conflicts == 0 {
-                break;
+            if accepted {
+                s.current.clone_from(&*tree);
+                s.current_obj = obj;
+            } else {
+                tree.clone_from(&s.current);
             }
 
-            exclude.clear();
-            for _ in 0..self.num_excludes {
-                let i = rng.gen_range(0, tree.len());
-                exclude.push(tree.swap_remove(i));
+            if (self.stop_on_feasible && num_conflicts == 0) || self.cancel.is_cancelled() {
+                break;
             }
 
-            tmp.clear();
-            while tmp.len() != g.num_vertices() - 1 {
-                tmp.clear();
-                // FIXME: use sample without replacement
-                rng.shuffle(&mut edges);
-                {
-                    let edges = tree.iter()
-                        .chain(edges.iter().filter(|e| !exclude.contains(*e)));
-                    tmp.extend(g.kruskal().edges(edges));
+            self.perturbation.perturb(tree, self.num_excludes, rng);
+        }
+
+        tree.clone_from(&s.best);
+
+        s.best_num_conflicts
+    }
+}
+
+// The state of the ils at the start of iteration iter
+struct IlsState<W> {
+    iter: u32,
+    best: Vec<Edge<StaticGraph>>,
+    best_weight: W,
+    best_num_conflicts: u32,
+    best_obj: W,
+    iters_no_impr: u32,
+    iters_restart: u32,
+    iters_restart_to_best: u32,
+    // the solution that is perturbed
+    current: Vec<Edge<StaticGraph>>,
+    current_obj: W,
+    acceptance: AcceptanceState<W>,
+    accept_next: bool,
+}
+
+impl<W: Weight> IlsState<W> {
+    fn new(p: &MstCcProblem<W>, tree: &[Edge<StaticGraph>]) -> Self {
+        IlsState {
+            iter: 0,
+            best: tree.to_vec(),
+            best_weight: sum_prop(&p.w, tree),
+            best_num_conflicts: u32::MAX,
+            best_obj: W::max_value(),
+            iters_no_impr: 0,
+            iters_restart: 0,
+            iters_restart_to_best: 0,
+            current: tree.to_vec(),
+            current_obj: W::max_value(),
+            acceptance: AcceptanceState {
+                history: vec![],
+                temperature: None,
+            },
+            accept_next: true,
+        }
+    }
+
+    fn save(&self, g: &StaticGraph, c: &mut Checkpoint) {
+        c.set("iter", self.iter);
+        c.set_edges(g, "best", &self.best);
+        c.set("best_weight", self.best_weight);
+        c.set("best_num_conflicts", self.best_num_conflicts);
+        c.set("best_obj", self.best_obj);
+        c.set("iters_no_impr", self.iters_no_impr);
+        c.set("iters_restart", self.iters_restart);
+        c.set("iters_restart_to_best", self.iters_restart_to_best);
+        c.set_edges(g, "current", &self.current);
+        c.set("current_obj", self.current_obj);
+        c.set_list("acceptance_history", &self.acceptance.history);
+        match self.acceptance.temperature {
+            Some(t) => c.set("acceptance_temperature", t),
+            None => c.set("acceptance_temperature", "none"),
+        }
+        c.set("accept_next", self.accept_next);
+    }
+
+    fn load(g: &StaticGraph, c: &Checkpoint) -> Result<Self, String> {
+        let temperature = match c.get::<String>("acceptance_temperature")?.as_str() {
+            "none" => None,
+            _ => Some(c.get("acceptance_temperature")?),
+        };
+        Ok(IlsState {
+            iter: c.get("iter")?,
+            best: c.get_edges(g, "best")?,
+            best_weight: c.get("best_weight")?,
+            best_num_conflicts: c.get("best_num_conflicts")?,
+            best_obj: c.get("best_obj")?,
+            iters_no_impr: c.get("iters_no_impr")?,
+            iters_restart: c.get("iters_restart")?,
+            iters_restart_to_best: c.get("iters_restart_to_best")?,
+            current: c.get_edges(g, "current")?,
+            current_obj: c.get("current_obj")?,
+            acceptance: AcceptanceState {
+                history: c.get_list("acceptance_history")?,
+                temperature,
+            },
+            accept_next: c.get("accept_next")?,
+        })
+    }
+}
+
+// Decides from which solution the ils continues after the local search of a perturbed solution
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Acceptance {
+    // continue from the best solution (or an equally good one)
+    Better,
+    // always continue from the new solution
+    RandomWalk,
+    // continue from the new solution if its objective is at most x% worse than the best
+    Threshold(f64),
+    // late acceptance hill climbing, continue from the new solution if it is not worse than the
+    // current solution or the current solution of len iterations ago
+    LateAcceptance(usize),
+    // continue from a worse solution with probability exp(-delta / t), where t starts at
+    // temperature and is multiplied by cooling in each iteration
+    Annealing { temperature: f64, cooling: f64 },
+}
+
+struct AcceptanceState<W> {
+    history: Vec<W>,
+    temperature: Option<f64>,
+}
+
+impl Acceptance {
+    fn accept<W: Weight>(
+        &self,
+        state: &mut AcceptanceState<W>,
+        obj: W,
+        current: W,
+        best: W,
+        iter: u32,
+        rng: &mut StreamRng,
+    ) -> bool {
+        match *self {
+            Acceptance::Better => obj <= best,
+            Acceptance::RandomWalk => true,
+            Acceptance::Threshold(x) => obj.to_f64() <= best.to_f64() * (1.0 + x / 100.0),
+            Acceptance::LateAcceptance(len) => {
+                if state.history.is_empty() {
+                    state.history = vec![obj; len.max(1)];
                 }
-                exclude.clear();
+                let i = iter as usize % state.history.len();
+                let accept = obj <= current || obj <= state.history[i];
+                state.history[i] = if accept { obj } else { current };
+                accept
+            }
+            Acceptance::Annealing {
+                temperature,
+                cooling,
+            } => {
+                let t = state.temperature.unwrap_or(temperature);
+                state.temperature = Some(t * cooling);
+                obj <= current || (t > 0.0 && rng.gen_f64() < (-(obj - current).to_f64() / t).exp())
             }
-            mem::swap(tree, &mut tmp);
         }
+    }
+}
 
-        tree.clone_from(&best);
+// The format of the ils-acceptance option: better, walk, threshold:x, late:len and
+// annealing:temperature:cooling
+impl FromStr for Acceptance {
+    type Err = String;
 
-        best_num_conflicts
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s.split(':').collect::<Vec<_>>();
+        let error = || format!("invalid acceptance {}", s);
+        let num = |i: usize| parts[i].parse::<f64>().map_err(|_| error());
+        match (parts[0], parts.len()) {
+            ("better", 1) => Ok(Acceptance::Better),
+            ("walk", 1) => Ok(Acceptance::RandomWalk),
+            ("threshold", 2) => Ok(Acceptance::Threshold(num(1)?)),
+            ("late", 2) => Ok(Acceptance::LateAcceptance(
+                parts[1].parse().map_err(|_| error())?,
+            )),
+            ("annealing", 3) => Ok(Acceptance::Annealing {
+                temperature: num(1)?,
+                cooling: num(2)?,
+            }),
+            _ => Err(error()),
+        }
+    }
+}
+
+impl fmt::Display for Acceptance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Acceptance::Better => write!(f, "better"),
+            Acceptance::RandomWalk => write!(f, "walk"),
+            Acceptance::Threshold(x) => write!(f, "threshold:{}", x),
+            Acceptance::LateAcceptance(len) => write!(f, "late:{}", len),
+            Acceptance::Annealing {
+                temperature,
+                cooling,
+            } => write!(f, "annealing:{}:{}", temperature, cooling),
+        }
     }
 }
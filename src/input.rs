@@ -1,36 +1,47 @@
 // system
 use std::error::Error;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{self, BufRead, BufReader, Write};
 use std::str::FromStr;
 
 // external
 use fera::graph::prelude::*;
 
 // local
-use MstCcProblem;
+use {verify, MstCcProblem, Weight};
 
-pub fn read_sammer_urrutia(file: &str) -> Result<MstCcProblem, Box<Error>> {
+pub fn read_sammer_urrutia<W: Weight>(file: &str) -> Result<MstCcProblem<W>, Box<dyn Error>> {
     debug!("Start read_sammer_urrutia: {}", file);
 
-    let lines = &mut BufReader::new(File::open(file)?)
+    let lines = BufReader::new(File::open(file)?)
         .lines()
-        .map(Result::unwrap)
-        .skip_while(|s| s.starts_with('#'));
+        .collect::<io::Result<Vec<_>>>()?;
+    let lines = &mut lines.into_iter().skip_while(|s| s.starts_with('#'));
+    let end = || format!("{}: unexpected end of file", file);
 
-    let name = lines.next().unwrap();
+    let name = lines.next().ok_or_else(end)?;
     let n: usize = parse_next(lines)?;
     let m: usize = parse_next(lines)?;
     let c: usize = parse_next(lines)?;
 
     let mut b = StaticGraph::builder(n, m);
-    let mut w = Vec::<u32>::new();
+    let mut w = Vec::<W>::new();
 
     for _ in 0..m {
-        let line = lines.next().unwrap();
+        let line = lines.next().ok_or_else(end)?;
         let s = &mut line.split_whitespace();
-        b.add_edge(parse_next(s)?, parse_next(s)?);
-        w.push(parse_next(s)?);
+        let (u, v) = (parse_next(s)?, parse_next(s)?);
+        if u >= n || v >= n {
+            return Err(format!("{}: invalid edge ({}, {})", file, u, v).into());
+        }
+        b.add_edge(u, v);
+        let weight = s
+            .next()
+            .ok_or_else(|| format!("{}: missing weight", file))?;
+        match weight.parse::<W>() {
+            Ok(value) if value.is_valid() => w.push(value),
+            _ => return Err(format!("{}: invalid weight {}", file, weight).into()),
+        }
     }
 
     let g = b.finalize();
@@ -38,12 +49,16 @@ pub fn read_sammer_urrutia(file: &str) -> Result<MstCcProblem, Box<Error>> {
     let mut num_cc = 0;
 
     for _ in 0..c {
-        let line = lines.next().unwrap();
+        let line = lines.next().ok_or_else(end)?;
         let s = &mut line.split_whitespace();
         let (a, b) = (parse_next(s)?, parse_next(s)?);
         let (x, y) = (parse_next(s)?, parse_next(s)?);
-        let ab = g.edge_by_ends(a, b);
-        let xy = g.edge_by_ends(x, y);
+        let edge = |u, v| {
+            g.get_edge_by_ends(u, v)
+                .ok_or_else(|| format!("{}: conflict with the non edge ({}, {})", file, u, v))
+        };
+        let ab = edge(a, b)?;
+        let xy = edge(x, y)?;
         cc[ab].push(xy);
         cc[xy].push(ab);
         num_cc += 1;
@@ -76,11 +91,74 @@ pub fn read_sammer_urrutia(file: &str) -> Result<MstCcProblem, Box<Error>> {
     })
 }
 
-fn parse_next<I, T>(iter: &mut I) -> Result<T, T::Err>
+pub fn write_sammer_urrutia<W: Weight, O: Write>(
+    p: &MstCcProblem<W>,
+    out: &mut O,
+) -> io::Result<()> {
+    let g = &p.g;
+    writeln!(out, "{}", p.name)?;
+    writeln!(out, "{}", g.num_vertices())?;
+    writeln!(out, "{}", g.num_edges())?;
+    writeln!(out, "{}", p.num_cc)?;
+    for (e, u, v) in g.edges_with_ends() {
+        writeln!(out, "{} {} {}", u, v, p.w[e])?;
+    }
+    for e in g.edges() {
+        for &f in p.cc[e].iter().filter(|&&f| e < f) {
+            let ((a, b), (x, y)) = (g.ends(e), g.ends(f));
+            writeln!(out, "{} {} {} {}", a, b, x, y)?;
+        }
+    }
+    Ok(())
+}
+
+// Reads a solution written as a list of u-v edges, like the one printed by the program. The
+// text up to the last comma of a line is ignored, so a full text or csv output line is also
+// accepted and the csv header is skipped. The json output is not accepted.
+pub fn read_solution<W: Weight>(
+    p: &MstCcProblem<W>,
+    file: &str,
+) -> Result<Vec<Edge<StaticGraph>>, Box<dyn Error>> {
+    let verification = verify(p, &read_solution_ends(file)?);
+    // conflicts are allowed, the solution may be used as the initial solution of the search
+    if let Some(v) = verification.violations.iter().find(|v| !v.is_conflict()) {
+        return Err(format!("{}: {}", file, v).into());
+    }
+    Ok(verification.tree)
+}
+
+pub fn read_solution_ends(file: &str) -> Result<Vec<(u32, u32)>, Box<dyn Error>> {
+    let mut ends = vec![];
+    for line in BufReader::new(File::open(file)?).lines() {
+        let line = line?;
+        if line.starts_with('#') {
+            continue;
+        }
+        let edges = line.rsplit(',').next().unwrap();
+        if edges == "solution" {
+            continue;
+        }
+        for edge in edges.split_whitespace() {
+            let s = &mut edge.splitn(2, '-');
+            let (u, v) = match (s.next(), s.next()) {
+                (Some(u), Some(v)) => (u.parse()?, v.parse()?),
+                _ => return Err(format!("{}: invalid edge {}", file, edge).into()),
+            };
+            ends.push((u, v));
+        }
+    }
+    Ok(ends)
+}
+
+fn parse_next<I, T>(iter: &mut I) -> Result<T, Box<dyn Error>>
 where
     I: Iterator,
     I::Item: AsRef<str>,
     T: FromStr,
+    T::Err: Error + 'static,
 {
-    iter.next().unwrap().as_ref().parse()
+    match iter.next() {
+        Some(s) => Ok(s.as_ref().parse()?),
+        None => Err("missing value".into()),
+    }
 }
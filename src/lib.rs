@@ -6,44 +6,115 @@ extern crate env_logger;
 extern crate fera;
 extern crate rand;
 
+mod bound;
+mod builder;
+mod cancel;
+mod checkpoint;
+mod config;
 mod conflicts;
 mod connectivity1;
 mod connectivity2;
 mod construct;
+mod generate;
+mod grasp;
 mod ils;
 mod input;
+mod json;
+mod matroid;
+mod observer;
 mod one;
+mod output;
+mod perturbation;
+mod solver;
+mod stats;
+mod summary;
+mod tune;
 mod two;
 mod utils;
+mod verify;
+mod weight;
 
+pub use bound::*;
+pub use builder::*;
+pub use cancel::*;
+pub use checkpoint::*;
+pub use config::*;
 pub use conflicts::*;
 pub use connectivity1::*;
 pub use connectivity2::*;
 pub use construct::*;
+pub use generate::*;
+pub use grasp::*;
 pub use ils::*;
 pub use input::*;
+pub use json::*;
+pub use matroid::*;
+pub use observer::*;
 pub use one::*;
+pub use output::*;
+pub use perturbation::*;
+pub use solver::*;
+pub use stats::*;
+pub use summary::*;
+pub use tune::*;
 pub use two::*;
 pub use utils::*;
+pub use verify::*;
+pub use weight::*;
 
 // system
 use std::cell::Cell;
 
 // external
+use fera::fun::vec;
+use fera::graph::algs::Kruskal;
+use fera::graph::params::IntoOwned;
 use fera::graph::prelude::*;
+use fera::graph::sum_prop;
 
-pub struct MstCcProblem {
+#[derive(Clone)]
+pub struct MstCcProblem<W = u32> {
     pub name: String,
     pub g: StaticGraph,
-    pub w: DefaultEdgePropMut<StaticGraph, u32>,
+    pub w: DefaultEdgePropMut<StaticGraph, W>,
     pub cc: DefaultEdgePropMut<StaticGraph, Vec<Edge<StaticGraph>>>,
     pub num_cc: usize,
     pub alpha: Cell<u32>,
     pub beta: Cell<u32>,
 }
 
-impl MstCcProblem {
-    fn obj(&self, weight: u32, num_conflicts: u32) -> u32 {
-        self.alpha.get() * weight + self.beta.get() * num_conflicts
+impl<W: Weight> MstCcProblem<W> {
+    pub fn obj(&self, weight: W, num_conflicts: u32) -> W {
+        W::from_u32(self.alpha.get()) * weight
+            + W::from_u32(self.beta.get()) * W::from_u32(num_conflicts)
+    }
+
+    pub fn weight<I>(&self, edges: I) -> W
+    where
+        I: IntoIterator,
+        I::Item: IntoOwned<Edge<StaticGraph>>,
+    {
+        sum_prop(&self.w, edges)
+    }
+
+    // A minimum spanning tree ignoring the conflicts
+    pub fn mst(&self) -> Vec<Edge<StaticGraph>> {
+        let mut edges = vec(self.g.edges());
+        sort_by_weight(&mut edges, |&e| self.w[e]);
+        vec(self.g.kruskal().edges(edges))
+    }
+
+    // Converts the weights to another type
+    pub fn map_weights<V: Weight, F: Fn(W) -> V>(self, f: F) -> MstCcProblem<V> {
+        let w = self.g.default_edge_prop_from_fn(|e| f(self.w[e]));
+        MstCcProblem {
+            name: self.name,
+            g: self.g,
+            w,
+            cc: self.cc,
+            num_cc: self.num_cc,
+            alpha: self.alpha,
+            beta: self.beta,
+        }
     }
 }
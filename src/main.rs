@@ -4,196 +4,995 @@ extern crate clap;
 #[macro_use]
 extern crate log;
 
+extern crate ctrlc;
 extern crate env_logger;
-extern crate fera;
 extern crate mstcc;
 extern crate rand;
 
 // internal
+use std::error::Error;
+use std::ffi::OsStr;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
 use std::time::Instant;
 
 // external
-use fera::fun::vec;
-use fera::graph::algs::Kruskal;
-use fera::graph::prelude::*;
-use fera::graph::sum_prop;
-use rand::Rng;
+use clap::{App, ArgMatches};
 
 // local
 use mstcc::*;
 
+// Calls the generic function f with the weight type selected by weights
+macro_rules! with_weights {
+    ($weights:expr, $f:ident($($arg:expr),*)) => {
+        match $weights {
+            WeightType::U32 => $f::<u32>($($arg),*),
+            WeightType::U64 => $f::<u64>($($arg),*),
+            WeightType::I64 => $f::<i64>($($arg),*),
+            WeightType::F64 => $f::<f64>($($arg),*),
+        }
+    };
+}
+
 pub fn main() {
-    let start = Instant::now();
-    let args = args();
+    let matches = app().get_matches();
+    match matches.subcommand() {
+        ("solve", Some(matches)) => solve_main(&args(matches)),
+        ("verify", Some(matches)) => verify_main(matches),
+        ("generate", Some(matches)) => generate_main(matches),
+        ("stats", Some(matches)) => stats_main(matches),
+        ("bound", Some(matches)) => bound_main(matches),
+        ("summary", Some(matches)) => summary_main(matches),
+        ("experiment", Some(matches)) => experiment_main(matches),
+        ("tune", Some(matches)) => tune_main(matches),
+        _ => unreachable!(),
+    }
+}
 
-    init_logger(&args.log_level);
+fn verify_main(matches: &ArgMatches) {
+    init_logger(matches.value_of("level").unwrap());
+
+    let weights = value_t_or_exit!(matches, "weights", WeightType);
+    with_weights!(weights, verify_instance(matches))
+}
+
+fn verify_instance<W: Weight>(matches: &ArgMatches) {
+    let p: MstCcProblem<W> = read_sammer_urrutia(matches.value_of("input").unwrap()).unwrap();
+    let ends = read_solution_ends(matches.value_of("solution").unwrap()).unwrap();
+    let verification = verify(&p, &ends);
+    let mut ok = verification.is_feasible();
+
+    println!("weight: {}", verification.weight);
+    println!("conflicts: {}", verification.num_conflicts);
+    for v in &verification.violations {
+        println!("{}", v);
+    }
+    if let Ok(weight) = value_t!(matches, "weight", W) {
+        if !weight.approx_eq(verification.weight) {
+            println!("weight {} != {}", weight, verification.weight);
+            ok = false;
+        }
+    }
+    if let Ok(conflicts) = value_t!(matches, "conflicts", u32) {
+        if conflicts != verification.num_conflicts {
+            println!("conflicts {} != {}", conflicts, verification.num_conflicts);
+            ok = false;
+        }
+    }
+
+    if ok {
+        println!("ok");
+    } else {
+        println!("fail");
+        process::exit(1);
+    }
+}
+
+fn generate_main(matches: &ArgMatches) {
+    let seed = value_t!(matches, "seed", Seed).unwrap_or_else(|_| Seed::new_random());
+    let n = value_t_or_exit!(matches, "n", usize);
+    let m = value_t_or_exit!(matches, "m", usize);
+    let c = value_t_or_exit!(matches, "c", usize);
+    let weights = (
+        value_t_or_exit!(matches, "min_weight", u32),
+        value_t_or_exit!(matches, "max_weight", u32),
+    );
+    if let Err(e) = check_random_instance(n, m, c, weights) {
+        exit_error(&e);
+    }
+    let name = matches
+        .value_of("name")
+        .map_or_else(|| format!("z{}-{}-{}", n, m, c), Into::into);
+
+    let mut rng = seed.new_rng(RngVersion::LATEST, Stream::Generate);
+    let p = new_random_instance(&name, n, m, c, weights, &mut rng);
+    let stdout = io::stdout();
+    let out = &mut stdout.lock();
+    writeln!(out, "# generated by mstcc generate with seed {}", seed).unwrap();
+    write_sammer_urrutia(&p, out).unwrap();
+}
+
+fn stats_main(matches: &ArgMatches) {
+    init_logger(matches.value_of("level").unwrap());
+
+    let weights = value_t_or_exit!(matches, "weights", WeightType);
+    with_weights!(weights, stats_instance(matches))
+}
+
+fn stats_instance<W: Weight>(matches: &ArgMatches) {
+    let p: MstCcProblem<W> = read_sammer_urrutia(matches.value_of("input").unwrap()).unwrap();
+    let stats = InstanceStats::new(&p);
+    match value_t_or_exit!(matches, "output_format", OutputFormat) {
+        OutputFormat::Json => println!("{}", stats.to_json()),
+        _ => print!("{}", stats),
+    }
+}
+
+fn bound_main(matches: &ArgMatches) {
+    init_logger(matches.value_of("level").unwrap());
+
+    let weights = value_t_or_exit!(matches, "weights", WeightType);
+    with_weights!(weights, bound_instance(matches))
+}
 
-    info!("Using {:?}", args.seed);
+fn bound_instance<W: Weight>(matches: &ArgMatches) {
+    let p: MstCcProblem<W> = read_sammer_urrutia(matches.value_of("input").unwrap()).unwrap();
+    println!("weight: {}", weight_lower_bound(&p));
+    println!("conflicts: {}", conflicts_lower_bound(&p));
+}
 
-    let p = read_sammer_urrutia(&args.file).unwrap();
-    let mut rng = args.seed.new_xor_shift_rng();
-    let mut edges = vec(p.g.edges());
-    let mut build = |tree: &mut Vec<_>| {
-        p.alpha.set(args.greedy_alpha);
-        p.beta.set(args.greedy_beta);
+fn summary_main(matches: &ArgMatches) {
+    init_logger(matches.value_of("level").unwrap());
 
-        tree.clear();
-        match args.init.as_str() {
-            "random" => {
-                rng.shuffle(&mut edges);
-                tree.extend(p.g.kruskal().edges(&edges));
+    let mut files = vec![];
+    for path in matches.values_of("input").unwrap() {
+        files.extend(expand_dir(path, None));
+    }
+    let csv = value_t_or_exit!(matches, "output_format", OutputFormat) == OutputFormat::Csv;
+    let stdout = io::stdout();
+    let out = &mut stdout.lock();
+    for file in files {
+        let results = match read_results(&file) {
+            Ok(results) => results,
+            Err(e) => {
+                warn!("Skipping {}: {}", file, e);
+                continue;
             }
-            "kruskal" => tree.extend(p.g.kruskal().weight(&p.w)),
-            "greedy" => new_greedy(&p, tree),
-            _ => unreachable!(),
         };
+        let summaries = summarize(&results);
+        if csv {
+            write_summary_csv(&summaries, out).unwrap();
+        } else {
+            writeln!(out, "{}", file).unwrap();
+            write_summary_table(&summaries, out).unwrap();
+            writeln!(out).unwrap();
+        }
+    }
+}
+
+// Returns the files of path with the given extension (or all files if extension is None) if it is
+// a directory (in natural order) or path otherwise
+fn expand_dir(path: &str, extension: Option<&str>) -> Vec<String> {
+    if Path::new(path).is_dir() {
+        let mut files = fs::read_dir(path)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.is_file())
+            .filter(|path| extension.map_or(true, |ext| path.extension() == Some(OsStr::new(ext))))
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        files.sort_by(|a, b| natural_cmp(a, b));
+        files
+    } else {
+        vec![path.into()]
+    }
+}
 
-        p.alpha.set(args.alpha);
-        p.beta.set(args.beta);
+// Solve options that are set by the experiment and not by each configuration
+const EXPERIMENT_OPTIONS: [&str; 6] = [
+    "seed",
+    "batch",
+    "repetitions",
+    "threads",
+    "output-format",
+    "log",
+];
+
+fn experiment_main(matches: &ArgMatches) {
+    init_logger(matches.value_of("level").unwrap());
+
+    let file = matches.value_of("input").unwrap();
+    let experiment = read_experiment(file).unwrap_or_else(|e| exit_error(&e.to_string()));
+    let dir = Path::new(file).parent().unwrap_or_else(|| Path::new(""));
+    let setting = |key, default: &str| {
+        matches
+            .value_of(key)
+            .map(String::from)
+            .or_else(|| experiment.setting(key).map(Json::to_plain_string))
+            .unwrap_or_else(|| default.into())
     };
+    let seed = setting("seed", &Seed::new_random().to_string());
+    let repetitions = setting("repetitions", "1");
+    let threads = setting("threads", "1");
+    let output_format = value_t_or_exit!(matches, "output_format", OutputFormat);
 
-    let mut tree = vec![];
-    build(&mut tree);
+    let mut header = true;
+    for config in &experiment.configs {
+        let error = |msg: &str| exit_error(&format!("config.{}: {}", config.name, msg));
+        for (key, _) in &config.params {
+            if EXPERIMENT_OPTIONS.contains(&key.as_str()) {
+                error(&format!(
+                    "{} must be set in the experiment, not in a config",
+                    key
+                ));
+            }
+        }
+        let options = vec![
+            "--batch".to_string(),
+            format!("--seed={}", seed),
+            format!("--repetitions={}", repetitions),
+            format!("--threads={}", threads),
+            format!(
+                "--output-format={}",
+                matches.value_of("output_format").unwrap()
+            ),
+        ];
+        let sets = if config.instances.is_empty() {
+            experiment.instances.iter().map(|s| &s.0).collect()
+        } else {
+            config.instances.iter().collect::<Vec<_>>()
+        };
+        let mut files = vec![];
+        for set in sets {
+            for f in experiment.instance_set(set).unwrap() {
+                files.push(dir.join(f).to_string_lossy().into_owned());
+            }
+        }
 
-    let mut rng = args.seed.new_xor_shift_rng();
+        let mut args = solve_args(options, &config.params, files).unwrap_or_else(|e| error(&e));
+        args.config = Some(config.name.clone());
+        args.output_format = output_format;
 
-    let mut ils = Ils {
-        p: &p,
-        max_iters: args.ils_max_iters,
-        max_iters_no_improv: args.ils_max_iters_no_improv,
-        num_excludes: args.ils_excludes,
-        iters_restart: args.ils_restart,
-        iters_restart_to_best: args.ils_restart_to_best,
-        restart: build,
-        stop_on_feasible: args.stop_on_feasible,
+        if header {
+            let stdout = io::stdout();
+            write_header(args.output_format, &args.params(), &mut stdout.lock()).unwrap();
+            header = false;
+        }
+
+        info!("Running config {}", config.name);
+        run_jobs(&args);
+    }
+}
+
+// Races candidate configurations (combinations of the values of the tuned parameters) on a
+// training set and writes the ranking of the surviving candidates and the best one as solve
+// options
+fn tune_main(matches: &ArgMatches) {
+    init_logger(matches.value_of("level").unwrap());
+
+    let seed = value_t!(matches, "seed", Seed).unwrap_or_else(|_| Seed::new_random());
+    let threads = value_t_or_exit!(matches, "threads", usize);
+    if threads == 0 {
+        exit_error("threads must be at least 1");
+    }
+    let budget = value_t_or_exit!(matches, "budget", usize);
+    let confidence = value_t_or_exit!(matches, "confidence", f64);
+    if confidence <= 0.0 || confidence >= 1.0 {
+        exit_error("confidence must be in (0, 1)");
+    }
+
+    let pair = |s: &str| {
+        let mut parts = s.splitn(2, '=');
+        match (parts.next(), parts.next()) {
+            (Some(key), Some(value)) if !key.is_empty() => (key.to_string(), value.to_string()),
+            _ => exit_error(&format!("expected key=value, found {}", s)),
+        }
+    };
+    let value = |s: &str| match s {
+        "true" => Json::Bool(true),
+        "false" => Json::Bool(false),
+        _ => Json::Str(s.into()),
+    };
+    let params = matches
+        .values_of("param")
+        .unwrap()
+        .map(|s| {
+            let (key, values) = pair(s);
+            (key, values.split(',').map(value).collect::<Vec<_>>())
+        })
+        .collect::<Vec<_>>();
+    let mut fixed = vec![
+        ("init".to_string(), value(matches.value_of("init").unwrap())),
+        ("alg".to_string(), value(matches.value_of("alg").unwrap())),
+    ];
+    if let Some(values) = matches.values_of("set") {
+        fixed.extend(values.map(|s| {
+            let (key, v) = pair(s);
+            (key, value(&v))
+        }));
+    }
+
+    let mut rng = seed.new_rng(RngVersion::LATEST, Stream::Tune);
+    let max_candidates = value_t_or_exit!(matches, "candidates", usize);
+    let configs = sample_configurations(&params, max_candidates, &mut rng);
+    let candidates = configs
+        .iter()
+        .map(|config| {
+            let params = fixed.iter().chain(config).cloned().collect::<Vec<_>>();
+            // the input is not used, the training instances are given to solve
+            solve_args(vec![], &params, vec!["-".into()]).unwrap_or_else(|e| exit_error(&e))
+        })
+        .collect::<Vec<_>>();
+
+    let files = matches
+        .values_of("input")
+        .map(|files| {
+            files
+                .flat_map(|f| expand_dir(f, Some("gcc")))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let generate = matches.value_of("generate").map(|s| {
+        let values = s
+            .split(',')
+            .map(str::parse)
+            .collect::<Result<Vec<usize>, _>>();
+        let (n, m, c) = match values {
+            Ok(ref values) if values.len() == 3 => (values[0], values[1], values[2]),
+            _ => exit_error("--generate must be n,m,c"),
+        };
+        if let Err(e) = check_random_instance(n, m, c, (1, 100)) {
+            exit_error(&format!("--generate: {}", e));
+        }
+        (n, m, c)
+    });
+    let instance_seeds = match generate {
+        Some(_) => {
+            let size = value_t_or_exit!(matches, "train_size", usize);
+            (0..size).map(|_| Seed::from(rng.next_u32())).collect()
+        }
+        None if files.is_empty() => exit_error("no training instances (use input or --generate)"),
+        None => vec![],
+    };
+    let num_instances = files.len().max(instance_seeds.len());
+    let load = |i: usize| match generate {
+        Some((n, m, c)) => {
+            let name = format!("z{}-{}-{}-{}", n, m, c, i);
+            let mut rng = instance_seeds[i].new_rng(RngVersion::LATEST, Stream::Generate);
+            new_random_instance(&name, n, m, c, (1, 100), &mut rng).map_weights(u64::from)
+        }
+        None => read_sammer_urrutia(&files[i]).unwrap_or_else(|e| exit_error(&e.to_string())),
     };
 
-    let mut one = OneEdgeReplacement::new(&p);
-    one.sort = args.sort;
-    one.stop_on_feasible = args.stop_on_feasible;
+    let cancel = cancel_token();
+    let mut race = Race::new(candidates.len());
+    race.first_test = value_t_or_exit!(matches, "first_test", usize);
+    race.alpha = 1.0 - confidence;
+    let mut runs = 0;
+    info!(
+        "Racing {} candidates on {} instances",
+        candidates.len(),
+        num_instances
+    );
+    while race.alive().len() > 1 && runs + race.alive().len() <= budget && !cancel.is_cancelled() {
+        let block = race.num_blocks();
+        let instance = block % num_instances;
+        let seed = Seed::from(seed.value().wrapping_add(block as u32));
+        let alive = race.alive().to_vec();
+        // the instance is read (or generated) once per block, each run gets a copy because solve
+        // sets alpha and beta of the problem
+        let p = load(instance);
+        let jobs = alive.iter().map(|&c| (c, p.clone())).collect();
+        // candidates are compared by the number of conflicts and then by the weight
+        let costs = parallel_map(jobs, threads, |(c, p)| {
+            let config = SolverConfig {
+                seed,
+                ..candidates[c].solver.clone()
+            };
+            let solution = solve(&p, &config);
+            (solution.num_conflicts, solution.weight)
+        });
+        if cancel.is_cancelled() {
+            // the runs of the block were stopped early, their costs are not comparable
+            break;
+        }
+        runs += alive.len();
+        let discarded = race.add_block(costs);
+        info!(
+            "Block {} (instance {}): discarded {}, {} alive",
+            block + 1,
+            instance,
+            discarded.len(),
+            race.alive().len()
+        );
+    }
 
-    let mut two = TwoEdgeReplacement::new(&p);
-    two.sort = args.sort;
-    two.stop_on_feasible = args.stop_on_feasible;
+    let flags = |config: &[(String, Json)]| {
+        config
+            .iter()
+            .filter_map(|(key, value)| match *value {
+                Json::Bool(true) => Some(format!("--{}", key)),
+                Json::Bool(false) => None,
+                _ => Some(format!("--{}={}", key, value.to_plain_string())),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    println!(
+        "# {} candidates, {} blocks, {} runs",
+        candidates.len(),
+        race.num_blocks(),
+        runs
+    );
+    if cancel.is_cancelled() {
+        println!("# interrupted, the ranking is partial and no configuration is selected");
+    }
+    println!("# mean rank of the surviving candidates");
+    for (i, rank) in race.ranking() {
+        println!("# {:.03} {}", rank, flags(&configs[i]));
+    }
+    if cancel.is_cancelled() {
+        process::exit(130);
+    }
+    println!("{}", flags(&configs[race.best()]));
+}
 
-    let conflicts = match args.alg.as_str() {
-        "2ex" => one.run(&mut tree),
-        "4ex" => two.run(&mut tree),
-        "2ex-4ex" => {
-            one.run(&mut tree);
-            two.run(&mut tree)
+// Returns [f(inputs[0]), ..., f(inputs[n - 1])] computed by threads threads
+fn parallel_map<I, T, F>(inputs: Vec<I>, threads: usize, f: F) -> Vec<T>
+where
+    I: Send,
+    T: Send,
+    F: Fn(I) -> T + Sync,
+{
+    let n = inputs.len();
+    let inputs = Mutex::new(inputs.into_iter().enumerate());
+    let results = Mutex::new((0..n).map(|_| None).collect::<Vec<_>>());
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| loop {
+                // the lock is released before f is called
+                let next = inputs.lock().unwrap().next();
+                let (i, input) = match next {
+                    Some(next) => next,
+                    None => break,
+                };
+                let result = f(input);
+                results.lock().unwrap()[i] = Some(result);
+            });
         }
-        "ils-2ex" => ils.run(&mut tree, &mut rng, |tree| one.run(tree)),
-        "ils-4ex" => ils.run(&mut tree, &mut rng, |tree| two.run(tree)),
-        "ils-2ex-4ex" => {
-            ils.run(&mut tree, &mut rng, |tree| one.run(tree));
-            ils.run(&mut tree, &mut rng, |tree| two.run(tree))
+    });
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(Option::unwrap)
+        .collect()
+}
+
+// Creates the solve arguments from options (like --seed=1) and the (long option, value) pairs of
+// a configuration. The init and alg pairs are the positional arguments and a true value is a flag.
+fn solve_args(
+    options: Vec<String>,
+    params: &[(String, Json)],
+    files: Vec<String>,
+) -> Result<Args, String> {
+    let mut argv = vec!["mstcc".to_string(), "solve".into()];
+    argv.extend(options);
+    let (mut init, mut alg) = (None, None);
+    for (key, value) in params {
+        match (key.as_str(), value) {
+            ("init", _) => init = Some(value.to_plain_string()),
+            ("alg", _) => alg = Some(value.to_plain_string()),
+            (_, &Json::Bool(true)) => argv.push(format!("--{}", key)),
+            (_, &Json::Bool(false)) => (),
+            _ => argv.push(format!("--{}={}", key, value.to_plain_string())),
         }
-        _ => unreachable!(),
+    }
+    match (init, alg) {
+        (Some(init), Some(alg)) => argv.extend(vec![init, alg]),
+        _ => return Err("init and alg must be set".into()),
+    }
+    argv.extend(files);
+
+    let matches = app().get_matches_from_safe(argv).map_err(|e| e.message)?;
+    Ok(args(matches.subcommand_matches("solve").unwrap()))
+}
+
+// The token cancelled by SIGINT and SIGTERM, the searches stop and the results of the jobs that
+// were running are written. A second signal exits immediately.
+fn cancel_token() -> &'static CancelToken {
+    static TOKEN: OnceLock<CancelToken> = OnceLock::new();
+    TOKEN.get_or_init(|| {
+        let token = CancelToken::new();
+        let handler = token.clone();
+        ctrlc::set_handler(move || {
+            if handler.is_cancelled() {
+                process::exit(130);
+            }
+            info!("Cancelled, writing the best solutions found");
+            handler.cancel();
+        })
+        .expect("the signal handler can be set only once");
+        token
+    })
+}
+
+fn exit_error(msg: &str) -> ! {
+    eprintln!("error: {}", msg);
+    process::exit(1)
+}
+
+fn solve_main(args: &Args) {
+    init_logger(&args.log_level);
+
+    if args.batch {
+        return batch(args);
+    }
+
+    let (file, seed) = (&args.files[0], args.solver.seed);
+    let report = run(args, file, seed, true).unwrap_or_else(|e| exit_error(&e.to_string()));
+    io::stdout().write_all(&report).unwrap();
+}
+
+// Runs the solver on each input file (or each file of an input directory) args.repetitions
+// times. Repetition i uses the seed args.solver.seed + i.
+fn batch(args: &Args) {
+    let stdout = io::stdout();
+    write_header(args.output_format, &args.params(), &mut stdout.lock()).unwrap();
+    run_jobs(args);
+}
+
+fn run_jobs(args: &Args) {
+    let mut files = vec![];
+    for file in &args.files {
+        files.extend(expand_dir(file, Some("gcc")));
+    }
+
+    let mut jobs = vec![];
+    for i in 0..args.repetitions {
+        let seed = Seed::from(args.solver.seed.value().wrapping_add(i));
+        jobs.extend(files.iter().map(|file| (file.as_str(), seed)));
+    }
+
+    info!("Running {} jobs in {} threads", jobs.len(), args.threads);
+
+    let stdout = io::stdout();
+    let next = AtomicUsize::new(0);
+    let output = Mutex::new(stdout);
+    thread::scope(|s| {
+        for _ in 0..args.threads {
+            s.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                if i >= jobs.len() || args.solver.cancel.is_cancelled() {
+                    break;
+                }
+                let (file, seed) = jobs[i];
+                info!("Job {}/{}: {} with {:?}", i + 1, jobs.len(), file, seed);
+                match run(args, file, seed, false) {
+                    Ok(report) => output.lock().unwrap().write_all(&report).unwrap(),
+                    Err(e) => warn!("Skipping job {}/{} ({}): {}", i + 1, jobs.len(), file, e),
+                }
+            });
+        }
+    });
+}
+
+// Solves file and returns the written report (with the header of the format if header is true)
+fn run(args: &Args, file: &str, seed: Seed, header: bool) -> Result<Vec<u8>, Box<dyn Error>> {
+    with_weights!(args.weights, run_instance(args, file, seed, header))
+}
+
+fn run_instance<W: Weight>(
+    args: &Args,
+    file: &str,
+    seed: Seed,
+    header: bool,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let start = Instant::now();
+    let p: MstCcProblem<W> = read_sammer_urrutia(file)?;
+    let mut config = SolverConfig {
+        seed,
+        ..args.solver.clone()
     };
+    if let Some(ref file) = args.init_from {
+        config.initial = Some(read_solution(&p, file)?);
+    }
+    if let Some(ref file) = config.checkpoint {
+        if args.resume && Path::new(file).exists() {
+            let c = Checkpoint::read(file).unwrap_or_else(|e| exit_error(&e.to_string()));
+            config.resume = Some(c);
+        }
+    }
+    if let Err(e) = config.check_checkpoint(&p) {
+        exit_error(&e);
+    }
+    let solution = solve(&p, &config);
 
     let elapsed = start.elapsed();
     let elapsed = elapsed.as_secs() as f64 + f64::from(elapsed.subsec_nanos()) / 1_000_000_000.0;
 
-    let weight: u32 = sum_prop(&p.w, &tree);
-    print!("{},{:.02},{},{},", p.name, elapsed, conflicts, weight);
-    for (u, v) in p.g.ends(tree) {
-        print!("{}-{} ", u, v);
+    let report = Report {
+        p: &p,
+        seed,
+        params: args.params(),
+        elapsed,
+        num_conflicts: solution.num_conflicts,
+        weight: solution.weight,
+        obj: solution.obj,
+        tree: &solution.tree,
+    };
+    let mut buffer = vec![];
+    if header {
+        report.write(args.output_format, &mut buffer)?;
+    } else {
+        report.write_row(args.output_format, &mut buffer)?;
     }
-    println!();
+    Ok(buffer)
 }
 
 struct Args {
-    seed: Seed,
+    // The name of the experiment configuration
+    config: Option<String>,
+    // The seed of the first repetition is solver.seed
+    solver: SolverConfig,
+    weights: WeightType,
     log_level: String,
-    alpha: u32,
-    beta: u32,
-    greedy_alpha: u32,
-    greedy_beta: u32,
-    sort: bool,
-    stop_on_feasible: bool,
-    ils_max_iters: u32,
-    ils_max_iters_no_improv: u32,
-    ils_excludes: u32,
-    ils_restart: u32,
-    ils_restart_to_best: u32,
-    init: String,
-    alg: String,
-    file: String,
-}
-
-fn args() -> Args {
-    let app = clap_app!(("mstcc") =>
+    init_from: Option<String>,
+    // continue from solver.checkpoint if it exists
+    resume: bool,
+    files: Vec<String>,
+    output_format: OutputFormat,
+    batch: bool,
+    repetitions: u32,
+    threads: usize,
+}
+
+impl Args {
+    fn params(&self) -> Vec<(&'static str, Json)> {
+        let mut params = vec![];
+        if let Some(ref config) = self.config {
+            params.push(("config", config.as_str().into()));
+        }
+        let solver = &self.solver;
+        params.extend(vec![
+            ("rng_version", solver.rng_version.value().into()),
+            ("weights", self.weights.to_string().into()),
+            ("init", solver.init.name().into()),
+            (
+                "init_from",
+                self.init_from
+                    .as_ref()
+                    .map_or(Json::Null, |s| s.as_str().into()),
+            ),
+            ("alg", solver.alg.name().into()),
+            ("alpha", solver.alpha.into()),
+            ("beta", solver.beta.into()),
+            ("greedy_alpha", solver.greedy_alpha.into()),
+            ("greedy_beta", solver.greedy_beta.into()),
+            ("sort", solver.sort.into()),
+            ("stop_on_feasible", solver.stop_on_feasible.into()),
+            ("no_exact", solver.no_exact.into()),
+            ("two_phase", solver.two_phase.into()),
+            (
+                "ils_max_iters",
+                solver.ils_max_iters.map_or_else(|| "m".into(), Into::into),
+            ),
+            (
+                "ils_max_iters_no_improv",
+                solver.ils_max_iters_no_improv.into(),
+            ),
+            ("ils_excludes", solver.ils_excludes.into()),
+            ("ils_perturbation", solver.ils_perturbation.name().into()),
+            ("ils_acceptance", solver.ils_acceptance.to_string().into()),
+            ("ils_restart", solver.ils_restart.into()),
+            ("ils_restart_to_best", solver.ils_restart_to_best.into()),
+            ("grasp_iters", solver.grasp_iters.into()),
+            ("grasp_rcl", solver.grasp_rcl.to_string().into()),
+        ]);
+        params
+    }
+}
+
+fn app() -> App<'static, 'static> {
+    clap_app!(("mstcc") =>
         (version: crate_version!())
         (author: crate_authors!())
         (about: "mstcc solver based on {2, 4}-exchange neighborhood")
-        (arg: arg_seed())
-        (arg: arg_log())
-        (@arg alpha: --alpha
-            default_value("1")
-            "The alpha value used in objective function")
-        (@arg beta: --beta
-            default_value("10000")
-            "The beta value used in objective function")
-        (@arg greedy_alpha: --("greedy-alpha")
-            default_value("1")
-            "The alpha value used in objective function in the greedy init heuristic")
-        (@arg greedy_beta: --("greedy-beta")
-            default_value("10000")
-            "The beta value used in objective function in the greedy init heuristic")
-        (@arg ils_max_iters: --("ils-max-iters")
-            default_value("1000")
-            "Maximum number of iterations for the ils algorithm")
-        (@arg ils_max_iters_no_improv: --("ils-max-iters-no-improv")
-            default_value("1000000000")
-            "Maximum number of iterations without improvement for the ils algorithm")
-        (@arg ils_restart: --("ils-restart")
-            default_value("1000000000")
-            "Maximum number of iterations without improvement for the ils algorithm to restart")
-        (@arg ils_restart_to_best: --("ils-restart-to-best")
-            default_value("1000000000")
-            "Maximum number of iterations without improvement for the ils algorithm to restart")
-        (@arg ils_excludes: --("ils-excludes")
-            default_value("1")
-            "Number of edges to exclude in the perturbation phase of the ils algorithm")
-        (@arg sort: --sort
-            "Sort the edges in 2ex")
-        (@arg stop_on_feasible: --("stop-on-feasible")
-            "Stop when the first feasible solution is found")
-        (@arg init: +required
-            possible_value("random")
-            possible_value("kruskal")
-            possible_value("greedy")
-            "The method used to create the initial solution")
-        (@arg alg: +required
-            possible_value("2ex")
-            possible_value("4ex")
-            possible_value("2ex-4ex")
-            possible_value("ils-2ex")
-            possible_value("ils-4ex")
-            possible_value("ils-2ex-4ex")
-            "The algorithm to run")
-        (arg: arg_input())
-    );
+        (@setting SubcommandRequiredElseHelp)
+        (@setting VersionlessSubcommands)
+        (@subcommand solve =>
+            (about: "Solve an instance")
+            (arg: arg_seed())
+            (@arg rng_version: --("rng-version")
+                possible_value("0")
+                possible_value("1")
+                possible_value("2")
+                default_value("2")
+                "The version of the random number streams (0 is the generator of the previous versions)")
+            (arg: arg_weights())
+            (arg: arg_log())
+            (@arg alpha: --alpha
+                default_value("1")
+                "The alpha value used in objective function")
+            (@arg beta: --beta
+                default_value("10000")
+                "The beta value used in objective function")
+            (@arg greedy_alpha: --("greedy-alpha")
+                default_value("1")
+                "The alpha value used in objective function in the greedy init heuristic")
+            (@arg greedy_beta: --("greedy-beta")
+                default_value("10000")
+                "The beta value used in objective function in the greedy init heuristic")
+            (@arg ils_max_iters: --("ils-max-iters")
+                default_value("1000")
+                "Maximum number of iterations for the ils algorithm (m means the number of edges)")
+            (@arg ils_max_iters_no_improv: --("ils-max-iters-no-improv")
+                default_value("1000000000")
+                "Maximum number of iterations without improvement for the ils algorithm")
+            (@arg ils_restart: --("ils-restart")
+                default_value("1000000000")
+                "Maximum number of iterations without improvement for the ils algorithm to restart")
+            (@arg ils_restart_to_best: --("ils-restart-to-best")
+                default_value("1000000000")
+                "Maximum number of iterations without improvement for the ils algorithm to restart")
+            (@arg ils_excludes: --("ils-excludes")
+                default_value("1")
+                "Number of edges to exclude in the perturbation phase of the ils algorithm")
+            (@arg ils_perturbation: --("ils-perturbation")
+                possible_value("random")
+                possible_value("conflicts")
+                possible_value("subtree")
+                possible_value("random-walk")
+                possible_value("double-bridge")
+                default_value("random")
+                "The perturbation of the ils algorithm: remove random edges, remove edges in \
+                 conflict first, remove a random subtree, make random edge replacements or remove \
+                 pairs of conflicting edges and reconnect with the least conflicting edges")
+            (@arg ils_acceptance: --("ils-acceptance")
+                default_value("walk")
+                "The solution from which the ils continues: better (the best), walk (the last), \
+                 threshold:x (the last if at most x% worse than the best), late:len (late \
+                 acceptance with a history of length len) or annealing:temperature:cooling")
+            (@arg grasp_iters: --("grasp-iters")
+                default_value("100")
+                "Number of constructions (and local searches) of the grasp algorithm")
+            (@arg grasp_rcl: --("grasp-rcl")
+                default_value("value:0.1")
+                "The restricted candidate list of the grasp construction: cardinality:alpha (the \
+                 alpha fraction of the candidates with the smallest cost) or value:alpha (the \
+                 candidates with cost at most min + alpha * (max - min))")
+            (@arg sort: --sort
+                "Sort the edges in 2ex")
+            (@arg stop_on_feasible: --("stop-on-feasible")
+                "Stop when the first feasible solution is found")
+            (@arg no_exact: --("no-exact")
+                "Do not use the exact algorithms: Kruskal when there are no conflicts and matroid \
+                 intersection when the conflicts are disjoint cliques (for example, a matching). \
+                 They are also not used with --init-from, --checkpoint or --stop-on-feasible")
+            (@arg two_phase: --("two-phase")
+                "First minimize only the number of conflicts (replacing only conflicting edges) \
+                 and then, if a feasible solution is found, minimize the weight without creating \
+                 conflicts")
+            (@arg init_from: --("init-from") +takes_value
+                "Read the initial solution from a file (a list of u-v edges or a text or csv output) instead of using init")
+            (@arg checkpoint: --checkpoint +takes_value conflicts_with("batch")
+                "Save the state of the ils (ils-2ex or ils-4ex) to this file periodically")
+            (@arg checkpoint_every: --("checkpoint-every")
+                default_value("100")
+                "Number of ils iterations between two checkpoints")
+            (@arg resume: --resume requires("checkpoint")
+                "Continue from the state saved in the checkpoint file (if the file exists), the \
+                 result is the same of a run that was not interrupted")
+            (@arg output_format: --("output-format")
+                possible_value("text")
+                possible_value("csv")
+                possible_value("json")
+                "The format used to write the result [default: csv with --batch, text otherwise]")
+            (@arg init: +required
+                possible_value("random")
+                possible_value("kruskal")
+                possible_value("greedy")
+                possible_value("prim-greedy")
+                "The method used to create the initial solution (and the restart solutions)")
+            (@arg alg: +required
+                possible_value("2ex")
+                possible_value("4ex")
+                possible_value("2ex-4ex")
+                possible_value("ils-2ex")
+                possible_value("ils-4ex")
+                possible_value("ils-2ex-4ex")
+                possible_value("grasp-2ex")
+                possible_value("grasp-4ex")
+                "The algorithm to run")
+            (@arg batch: --batch
+                "Solve all the input files (the files of a directory input) in one process")
+            (@arg repetitions: --repetitions
+                default_value("1")
+                "Number of times each input is solved in batch mode, repetition i uses seed + i")
+            (@arg threads: --threads
+                default_value("1")
+                "Number of threads used in batch mode")
+            (arg: arg_input().multiple(true).help("The input file (more than one in batch mode)"))
+        )
+        (@subcommand verify =>
+            (about: "Check that a solution is a feasible spanning tree of an instance")
+            (arg: arg_weights())
+            (arg: arg_log())
+            (@arg weight: --weight +takes_value
+                "The expected weight of the solution")
+            (@arg conflicts: --conflicts +takes_value
+                "The expected number of conflicts of the solution")
+            (arg: arg_input())
+            (@arg solution: +required
+                "The solution file (a list of u-v edges or a text or csv output)")
+        )
+        (@subcommand generate =>
+            (about: "Generate a random instance and write it to stdout")
+            (arg: arg_seed())
+            (@arg name: --name +takes_value
+                "The name of the instance [default: z<n>-<m>-<c>]")
+            (@arg min_weight: --("min-weight")
+                default_value("1")
+                "The minimum weight of an edge")
+            (@arg max_weight: --("max-weight")
+                default_value("100")
+                "The maximum weight of an edge")
+            (@arg n: +required "The number of vertices")
+            (@arg m: +required "The number of edges")
+            (@arg c: +required "The number of conflicting pairs of edges")
+        )
+        (@subcommand stats =>
+            (about: "Show statistics of an instance")
+            (arg: arg_weights())
+            (arg: arg_log())
+            (@arg output_format: --("output-format")
+                possible_value("text")
+                possible_value("json")
+                default_value("text")
+                "The format used to write the statistics")
+            (arg: arg_input())
+        )
+        (@subcommand bound =>
+            (about: "Compute lower bounds for an instance")
+            (arg: arg_weights())
+            (arg: arg_log())
+            (arg: arg_input())
+        )
+        (@subcommand experiment =>
+            (about: "Run the algorithm configurations of an experiment file on its instances")
+            (@arg seed: -s --seed +takes_value
+                "The seed used in the random number generator (overrides the experiment seed)")
+            (@arg threads: --threads +takes_value
+                "Number of threads (overrides the experiment threads)")
+            (arg: arg_log())
+            (@arg output_format: --("output-format")
+                possible_value("text")
+                possible_value("csv")
+                possible_value("json")
+                default_value("csv")
+                "The format used to write the results")
+            (@arg input: +required
+                "The experiment file")
+        )
+        (@subcommand tune =>
+            (about: "Tune solver parameters racing candidate configurations on a training set")
+            (arg: arg_seed())
+            (arg: arg_log())
+            (@arg param: --param +takes_value +multiple number_of_values(1) +required
+                "A parameter to tune and its candidate values (e.g. ils-excludes=1,2,3)")
+            (@arg set: --set +takes_value +multiple number_of_values(1)
+                "A fixed solve option (e.g. ils-max-iters=m)")
+            (@arg candidates: --candidates
+                default_value("64")
+                "Maximum number of candidate configurations (sampled from all combinations)")
+            (@arg budget: --budget
+                default_value("1000")
+                "Maximum number of runs of the solver")
+            (@arg first_test: --("first-test")
+                default_value("5")
+                "Number of instances evaluated before the first statistical test")
+            (@arg confidence: --confidence
+                default_value("0.95")
+                "Confidence level of the statistical tests")
+            (@arg generate: --generate +takes_value conflicts_with("input")
+                "Use random instances with n vertices, m edges and c conflicts (n,m,c) as training set")
+            (@arg train_size: --("train-size")
+                default_value("10")
+                "Number of random training instances")
+            (@arg threads: --threads
+                default_value("1")
+                "Number of threads")
+            (@arg init: +required
+                "The method used to create the initial solution (see solve)")
+            (@arg alg: +required
+                "The algorithm to tune (see solve)")
+            (@arg input: +multiple
+                "The training instances (files or directories)")
+        )
+        (@subcommand summary =>
+            (about: "Summarize the csv results of the solve subcommand by instance")
+            (arg: arg_log())
+            (@arg output_format: --("output-format")
+                possible_value("text")
+                possible_value("csv")
+                default_value("text")
+                "The format used to write the summary")
+            (@arg input: +required +multiple
+                "The results files or directories")
+        )
+    )
+}
 
-    let matches = app.get_matches();
+fn args(matches: &ArgMatches) -> Args {
+    if !matches.is_present("batch") && matches.occurrences_of("input") > 1 {
+        clap::Error::with_description(
+            "More than one input file can only be used with --batch",
+            clap::ErrorKind::TooManyValues,
+        )
+        .exit();
+    }
 
-    Args {
+    let solver = SolverConfig {
         seed: value_t!(matches, "seed", Seed).unwrap_or_else(|_| Seed::new_random()),
-        log_level: matches.value_of("level").unwrap().into(),
+        rng_version: value_t_or_exit!(matches, "rng_version", RngVersion),
         alpha: value_t_or_exit!(matches, "alpha", u32),
         beta: value_t_or_exit!(matches, "beta", u32),
-        init: matches.value_of("init").unwrap().into(),
         greedy_alpha: value_t_or_exit!(matches, "greedy_alpha", u32),
         greedy_beta: value_t_or_exit!(matches, "greedy_beta", u32),
         sort: matches.is_present("sort"),
         stop_on_feasible: matches.is_present("stop_on_feasible"),
-        ils_max_iters: value_t_or_exit!(matches, "ils_max_iters", u32),
+        no_exact: matches.is_present("no_exact"),
+        two_phase: matches.is_present("two_phase"),
+        ils_max_iters: match matches.value_of("ils_max_iters").unwrap() {
+            "m" => None,
+            _ => Some(value_t_or_exit!(matches, "ils_max_iters", u32)),
+        },
         ils_max_iters_no_improv: value_t_or_exit!(matches, "ils_max_iters_no_improv", u32),
         ils_excludes: value_t_or_exit!(matches, "ils_excludes", u32),
+        ils_perturbation: value_t_or_exit!(matches, "ils_perturbation", PerturbationKind),
+        ils_acceptance: value_t_or_exit!(matches, "ils_acceptance", Acceptance),
         ils_restart: value_t_or_exit!(matches, "ils_restart", u32),
         ils_restart_to_best: value_t_or_exit!(matches, "ils_restart_to_best", u32),
-        alg: matches.value_of("alg").unwrap().into(),
-        file: matches.value_of("input").unwrap().into(),
+        grasp_iters: value_t_or_exit!(matches, "grasp_iters", u32),
+        grasp_rcl: value_t_or_exit!(matches, "grasp_rcl", Rcl),
+        init: value_t_or_exit!(matches, "init", Init),
+        initial: None,
+        alg: value_t_or_exit!(matches, "alg", Algorithm),
+        cancel: cancel_token().clone(),
+        checkpoint: matches.value_of("checkpoint").map(Into::into),
+        checkpoint_every: value_t_or_exit!(matches, "checkpoint_every", u32),
+        resume: None,
+    };
+    let threads = value_t_or_exit!(matches, "threads", usize);
+    if threads == 0 {
+        exit_error("threads must be at least 1");
+    }
+
+    Args {
+        config: None,
+        solver,
+        weights: value_t_or_exit!(matches, "weights", WeightType),
+        log_level: matches.value_of("level").unwrap().into(),
+        init_from: matches.value_of("init_from").map(Into::into),
+        resume: matches.is_present("resume"),
+        files: matches
+            .values_of("input")
+            .unwrap()
+            .map(Into::into)
+            .collect(),
+        // the text format has no header and no seed, the rows of a batch could not be told apart
+        output_format: match matches.value_of("output_format") {
+            Some(_) => value_t_or_exit!(matches, "output_format", OutputFormat),
+            None if matches.is_present("batch") => OutputFormat::Csv,
+            None => OutputFormat::Text,
+        },
+        batch: matches.is_present("batch"),
+        repetitions: value_t_or_exit!(matches, "repetitions", u32),
+        threads,
     }
 }
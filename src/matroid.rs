@@ -0,0 +1,247 @@
+// system
+use std::collections::VecDeque;
+
+// external
+use fera::fun::vec;
+use fera::graph::prelude::*;
+
+// local
+use {MstCcProblem, Weight};
+
+const NONE: usize = usize::MAX;
+
+// Exact solver for the instances where the conflict graph is a union of disjoint cliques (a
+// matching is a special case). In this case the sets with at most one edge of each clique are
+// the independent sets of a partition matroid and a conflict free spanning tree of minimum weight
+// is a minimum weight common base of this matroid and the graphic matroid of g, which is found by
+// the weighted matroid intersection algorithm (Schrijver, Combinatorial Optimization, 41.3).
+pub struct MatroidIntersection<'a, W: 'a> {
+    p: &'a MstCcProblem<W>,
+    edges: Vec<Edge<StaticGraph>>,
+    // group[i] is the clique of edges[i], the edges without conflicts are cliques of size one
+    group: Vec<usize>,
+    num_groups: usize,
+}
+
+impl<'a, W: Weight> MatroidIntersection<'a, W> {
+    // Returns None if the conflict graph is not a union of disjoint cliques
+    pub fn new(p: &'a MstCcProblem<W>) -> Option<Self> {
+        let g = &p.g;
+        let edges = vec(g.edges());
+        let index = g.edge_index();
+        let mut group = vec![NONE; edges.len()];
+        let mut num_groups = 0;
+        let mut stack = vec![];
+        let mut clique = vec![];
+        for i in 0..edges.len() {
+            if group[i] != NONE {
+                continue;
+            }
+            clique.clear();
+            group[i] = num_groups;
+            stack.push(i);
+            while let Some(j) = stack.pop() {
+                clique.push(j);
+                for &f in &p.cc[edges[j]] {
+                    let k = index.get(f);
+                    if group[k] == NONE {
+                        group[k] = num_groups;
+                        stack.push(k);
+                    }
+                }
+            }
+            for &j in &clique {
+                let mut neighbors = p.cc[edges[j]]
+                    .iter()
+                    .map(|&f| index.get(f))
+                    .filter(|&k| k != j)
+                    .collect::<Vec<_>>();
+                neighbors.sort();
+                neighbors.dedup();
+                if neighbors.len() + 1 != clique.len() {
+                    return None;
+                }
+            }
+            num_groups += 1;
+        }
+        Some(MatroidIntersection {
+            p,
+            edges,
+            group,
+            num_groups,
+        })
+    }
+
+    // Returns a conflict free spanning tree with minimum weight or, if there is no conflict free
+    // spanning tree, a conflict free forest with the maximum number of edges (which proves the
+    // infeasibility, as it has less than n - 1 edges)
+    pub fn solve(&self) -> Result<Vec<Edge<StaticGraph>>, Vec<Edge<StaticGraph>>> {
+        let n = self.p.g.num_vertices();
+        let mut in_set = vec![false; self.edges.len()];
+        let mut size = 0;
+        while size + 1 < n {
+            match self.shortest_path(&in_set) {
+                Some(path) => {
+                    for i in path {
+                        in_set[i] = !in_set[i];
+                    }
+                    size += 1;
+                }
+                None => break,
+            }
+        }
+        let forest = self
+            .edges
+            .iter()
+            .zip(&in_set)
+            .filter(|&(_, &in_set)| in_set)
+            .map(|(&e, _)| e)
+            .collect::<Vec<_>>();
+        if size + 1 >= n {
+            Ok(forest)
+        } else {
+            Err(forest)
+        }
+    }
+
+    // Finds a shortest path (by length and then by number of arcs) in the exchange graph of the
+    // common independent set in_set, where the length of an edge not in the set is its weight and
+    // of an edge in the set is its negated weight
+    fn shortest_path(&self, in_set: &[bool]) -> Option<Vec<usize>> {
+        let g = &self.p.g;
+        let index = g.edge_index();
+        let m = self.edges.len();
+
+        // the edge of the set in each group
+        let mut owner = vec![NONE; self.num_groups];
+        for i in (0..m).filter(|&i| in_set[i]) {
+            owner[self.group[i]] = i;
+        }
+
+        // the forest of the set rooted in each component
+        let mut comp = g.default_vertex_prop(NONE);
+        let mut depth = g.default_vertex_prop(0);
+        let mut parent = g.default_vertex_prop(NONE);
+        let mut queue = VecDeque::new();
+        for (i, root) in g.vertices().enumerate() {
+            if comp[root] != NONE {
+                continue;
+            }
+            comp[root] = i;
+            queue.push_back(root);
+            while let Some(u) = queue.pop_front() {
+                for e in g.out_edges(u) {
+                    let v = g.target(e);
+                    if in_set[index.get(e)] && comp[v] == NONE {
+                        comp[v] = comp[root];
+                        depth[v] = depth[u] + 1;
+                        parent[v] = index.get(e);
+                        queue.push_back(v);
+                    }
+                }
+            }
+        }
+
+        // arcs (y, x) from y in the set to x not in the set where set - y + x is a forest, the
+        // arcs to the sources are not stored
+        let mut out = vec![vec![]; m];
+        let mut sources = vec![];
+        for x in (0..m).filter(|&x| !in_set[x]) {
+            let (mut u, mut v) = g.ends(self.edges[x]);
+            if comp[u] != comp[v] {
+                sources.push(x);
+                continue;
+            }
+            while u != v {
+                if depth[u] < depth[v] {
+                    ::std::mem::swap(&mut u, &mut v);
+                }
+                let y = parent[u];
+                out[y].push(x);
+                u = g.opposite(u, self.edges[y]);
+            }
+        }
+        let members = (0..m).filter(|&i| in_set[i]).collect::<Vec<_>>();
+
+        let zero = W::default();
+        let len = |i: usize| {
+            let w = self.p.w[self.edges[i]];
+            if in_set[i] {
+                Dist(zero, w, 1)
+            } else {
+                Dist(w, zero, 1)
+            }
+        };
+        let mut dist: Vec<Option<Dist<W>>> = vec![None; m];
+        let mut pred = vec![NONE; m];
+        let mut in_queue = vec![false; m];
+        let mut queue = VecDeque::new();
+        for &x in &sources {
+            dist[x] = Some(Dist(len(x).0, zero, 0));
+            in_queue[x] = true;
+            queue.push_back(x);
+        }
+        let mut targets = vec![];
+        while let Some(a) = queue.pop_front() {
+            in_queue[a] = false;
+            targets.clear();
+            if in_set[a] {
+                targets.extend(&out[a]);
+                targets.extend(&sources);
+            } else if owner[self.group[a]] != NONE {
+                // set - y + a has at most one edge in the group of a only if y is the owner
+                targets.push(owner[self.group[a]]);
+            } else {
+                targets.extend(&members);
+            }
+            let d = dist[a].unwrap();
+            for &b in &targets {
+                let new = d.add(len(b));
+                let shorter = match dist[b] {
+                    Some(old) => new.less(&old),
+                    None => true,
+                };
+                if shorter {
+                    dist[b] = Some(new);
+                    pred[b] = a;
+                    if !in_queue[b] {
+                        in_queue[b] = true;
+                        queue.push_back(b);
+                    }
+                }
+            }
+        }
+
+        // the sinks are the edges not in the set whose group has no edge in the set
+        let sink = (0..m)
+            .filter(|&x| !in_set[x] && owner[self.group[x]] == NONE && dist[x].is_some())
+            .fold(None, |best: Option<usize>, x| match best {
+                Some(y) if !dist[x].unwrap().less(&dist[y].unwrap()) => best,
+                _ => Some(x),
+            })?;
+        let mut path = vec![sink];
+        while pred[*path.last().unwrap()] != NONE {
+            let a = pred[*path.last().unwrap()];
+            path.push(a);
+        }
+        Some(path)
+    }
+}
+
+// The length of a path as the sum of the weights of the edges not in the set, the sum of the
+// weights of the edges in the set (the length is the difference) and the number of arcs, so
+// unsigned weights can be used
+#[derive(Copy, Clone)]
+struct Dist<W>(W, W, usize);
+
+impl<W: Weight> Dist<W> {
+    fn add(self, other: Self) -> Self {
+        Dist(self.0 + other.0, self.1 + other.1, self.2 + other.2)
+    }
+
+    // Compares by length and then by number of arcs
+    fn less(&self, other: &Self) -> bool {
+        let (a, b) = (self.0 + other.1, other.0 + self.1);
+        a < b || (a == b && self.2 < other.2)
+    }
+}
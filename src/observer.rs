@@ -0,0 +1,54 @@
+// local
+use {log_improvement, Weight};
+
+// The number of conflicts and the weight of a solution
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Cost<W> {
+    pub num_conflicts: u32,
+    pub weight: W,
+}
+
+// Receives the events of the searches, alg is the name of the search that sends the event (ils,
+// grasp, 2ex or 4ex). The methods take &self because the same observer is used by the ils and by
+// its local search, so an observer that keeps state needs interior mutability.
+pub trait Observer<W> {
+    // A solution better than the best one was found in iteration iter (the old cost of the first
+    // solution has u32::MAX conflicts)
+    fn on_improvement(&self, _alg: &str, _iter: u32, _old: Cost<W>, _new: Cost<W>) {}
+
+    // The local search of iteration iter ended with a solution of cost cost
+    fn on_iteration(&self, _alg: &str, _iter: u32, _cost: Cost<W>) {}
+
+    // The search restarted from a new solution or, if to_best, from the best solution
+    fn on_restart(&self, _alg: &str, _iter: u32, _to_best: bool) {}
+
+    // A local search replaced edges of the tree
+    fn on_move(&self, _alg: &str, _old: Cost<W>, _new: Cost<W>) {}
+}
+
+// Logs the events (the logging done by the searches before the observers)
+pub struct LogObserver;
+
+impl<W: Weight> Observer<W> for LogObserver {
+    fn on_improvement(&self, alg: &str, iter: u32, old: Cost<W>, new: Cost<W>) {
+        info!("{} - iter      {}", alg, iter);
+        info!(
+            "{} - conflicts {} -> {}",
+            alg, old.num_conflicts, new.num_conflicts
+        );
+        info!("{} - weight    {} -> {}", alg, old.weight, new.weight);
+    }
+
+    fn on_restart(&self, alg: &str, _iter: u32, to_best: bool) {
+        if to_best {
+            info!("{} - restart to best", alg);
+        } else {
+            info!("{} - restart", alg);
+        }
+    }
+
+    fn on_move(&self, _alg: &str, old: Cost<W>, new: Cost<W>) {
+        log_improvement("conflicts", old.num_conflicts, new.num_conflicts);
+        log_improvement("weight   ", old.weight, new.weight);
+    }
+}
@@ -4,36 +4,48 @@ use std::mem;
 // external
 use fera::graph::algs::Trees;
 use fera::graph::prelude::*;
-use fera::graph::props::FnProp;
 use fera::graph::sum_prop;
 
 // local
-use {log_improvement, MstCcProblem, TrackConflicts, TrackConnectivity1};
+use {
+    sort_by_weight, CancelToken, Cost, LogObserver, MstCcProblem, Observer, TrackConflicts,
+    TrackConnectivity1, Weight,
+};
 
-pub struct OneEdgeReplacement<'a> {
-    p: &'a MstCcProblem,
+pub struct OneEdgeReplacement<'a, W: 'a> {
+    p: &'a MstCcProblem<W>,
     in_tree: DefaultEdgePropMut<StaticGraph, bool>,
     non_tree: Vec<Edge<StaticGraph>>,
     conflicts: TrackConflicts<'a>,
     connectivity: TrackConnectivity1<'a, StaticGraph>,
-    weight: u32,
+    weight: W,
     num_conflicts: u32,
     pub sort: bool,
     pub stop_on_feasible: bool,
+    // only replace tree edges with conflicts
+    pub only_conflicting: bool,
+    // only do replacements that do not increase the number of conflicts
+    pub feasible_only: bool,
+    pub observer: &'a dyn Observer<W>,
+    pub cancel: CancelToken,
 }
 
-impl<'a> OneEdgeReplacement<'a> {
-    pub fn new(p: &'a MstCcProblem) -> Self {
+impl<'a, W: Weight> OneEdgeReplacement<'a, W> {
+    pub fn new(p: &'a MstCcProblem<W>) -> Self {
         OneEdgeReplacement {
             p,
             non_tree: Vec::with_capacity(p.g.num_edges()),
             in_tree: p.g.edge_prop(false),
             conflicts: TrackConflicts::new(p),
             connectivity: TrackConnectivity1::new(&p.g),
-            weight: 0,
+            weight: W::default(),
             num_conflicts: 0,
             sort: false,
             stop_on_feasible: false,
+            only_conflicting: false,
+            feasible_only: false,
+            observer: &LogObserver,
+            cancel: CancelToken::new(),
         }
     }
 
@@ -46,13 +58,22 @@ impl<'a> OneEdgeReplacement<'a> {
             if self.stop_on_feasible && self.conflicts.total() == 0 {
                 break;
             }
+            if self.cancel.is_cancelled() {
+                break;
+            }
             if !self.one_replacement(tree) {
                 break;
             }
         }
 
-        let expected_weight: u32 = sum_prop(&self.p.w, &*tree);
-        assert_eq!(expected_weight, self.weight);
+        let expected_weight: W = sum_prop(&self.p.w, &*tree);
+        // the sum depends on the order of the edges if the weights are floating point
+        assert!(
+            expected_weight.approx_eq(self.weight),
+            "{} != {}",
+            expected_weight,
+            self.weight
+        );
 
         debug!("End one-edge-replacement with weight = {}", self.weight);
 
@@ -65,6 +86,10 @@ impl<'a> OneEdgeReplacement<'a> {
         self.sort(tree);
 
         for i in 0..tree.len() {
+            if self.only_conflicting && self.conflicts[tree[i]] == 0 {
+                continue;
+            }
+
             let (rem, a, b) = self.p.g.ends(tree[i]);
 
             self.conflicts.remove_edge(rem);
@@ -85,9 +110,9 @@ impl<'a> OneEdgeReplacement<'a> {
         if self.sort {
             let p = &self.p;
             let conflicts = &self.conflicts;
-            let obj = FnProp(|e| p.obj(p.w.get(e), conflicts[e]));
-            self.non_tree.sort_by_prop(&obj);
-            tree.sort_by_prop(&obj);
+            let obj = |e: &Edge<StaticGraph>| p.obj(p.w[*e], conflicts[*e]);
+            sort_by_weight(&mut self.non_tree, &obj);
+            sort_by_weight(tree, &obj);
             tree.reverse();
         }
     }
@@ -96,7 +121,11 @@ impl<'a> OneEdgeReplacement<'a> {
         for j in 0..self.non_tree_limit(rem) {
             let ins = self.non_tree[j];
 
-            if self.obj_edge(ins) >= self.obj_edge(rem) {
+            if !self.obj_edge(ins).improves(self.obj_edge(rem)) {
+                continue;
+            }
+
+            if self.feasible_only && self.conflicts[ins] > self.conflicts[rem] {
                 continue;
             }
 
@@ -117,8 +146,9 @@ impl<'a> OneEdgeReplacement<'a> {
             let w = self.p.w.get(rem);
             let c = self.conflicts[rem] + 1;
             let key = self.p.obj(w, c);
-            match self.non_tree
-                .binary_search_by_key(&key, |e| self.obj_edge(*e))
+            match self
+                .non_tree
+                .binary_search_by(|e| self.obj_edge(*e).cmp_weight(&key))
             {
                 Ok(m) | Err(m) => m,
             }
@@ -138,17 +168,25 @@ impl<'a> OneEdgeReplacement<'a> {
         self.conflicts.add_edge(ins);
 
         let num_conflicts = self.conflicts.total();
-        let weight = self.weight + self.p.w.get(ins) - self.p.w.get(rem);
-
-        log_improvement("conflicts", self.num_conflicts, num_conflicts);
-        log_improvement("weight   ", self.weight, weight);
+        // summed again (instead of updated) to avoid accumulating rounding errors
+        let weight = sum_prop(&self.p.w, &*tree);
+
+        let old = Cost {
+            num_conflicts: self.num_conflicts,
+            weight: self.weight,
+        };
+        let new = Cost {
+            num_conflicts,
+            weight,
+        };
+        self.observer.on_move("2ex", old, new);
 
         self.num_conflicts = num_conflicts;
         self.weight = weight;
     }
 
     fn setup(&mut self, tree: &[Edge<StaticGraph>]) {
-        self.connectivity.set_edges(&*tree);
+        self.connectivity.set_edges(tree);
 
         self.conflicts.reset();
         self.conflicts.add_edges(tree);
@@ -162,7 +200,7 @@ impl<'a> OneEdgeReplacement<'a> {
         let g = &self.p.g;
         self.non_tree.extend(g.edges().filter(|e| !in_tree[*e]));
 
-        self.weight = sum_prop(&self.p.w, &*tree);
+        self.weight = sum_prop(&self.p.w, tree);
         self.num_conflicts = self.conflicts.total();
     }
 
@@ -172,7 +210,7 @@ impl<'a> OneEdgeReplacement<'a> {
         assert!(g.spanning_subgraph(self.conflicts.edges()).is_tree());
     }
 
-    fn obj_edge(&self, e: Edge<StaticGraph>) -> u32 {
+    fn obj_edge(&self, e: Edge<StaticGraph>) -> W {
         self.p.obj(self.p.w.get(e), self.conflicts[e])
     }
 }
@@ -0,0 +1,171 @@
+// system
+use std::borrow::Cow;
+use std::io::{self, Write};
+use std::mem;
+use std::str::FromStr;
+
+// external
+use fera::graph::prelude::*;
+
+// local
+use {Json, MstCcProblem, Seed, TrackConflicts, Weight};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum OutputFormat {
+    // name,time,conflicts,weight,solution without header (the original format)
+    Text,
+    Csv,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "csv" => Ok(OutputFormat::Csv),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!("invalid output format: {}", s)),
+        }
+    }
+}
+
+// Only the csv format has a header. The json format writes one object per line.
+pub fn write_header<W: Write>(
+    format: OutputFormat,
+    params: &[(&'static str, Json)],
+    out: &mut W,
+) -> io::Result<()> {
+    if format == OutputFormat::Csv {
+        write!(out, "name,seed,")?;
+        for &(name, _) in params {
+            write!(out, "{},", name)?;
+        }
+        writeln!(out, "time,conflicts,weight,obj,solution")?;
+    }
+    Ok(())
+}
+
+// Quotes a csv field that has a comma, a quote or a line break, doubling its quotes (RFC 4180)
+pub fn csv_field(s: &str) -> Cow<'_, str> {
+    if s.contains(&[',', '"', '\n', '\r'][..]) {
+        Cow::Owned(format!("\"{}\"", s.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
+// Splits a csv line in fields, removing the quotes written by csv_field
+pub fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+pub struct Report<'a, W: 'a> {
+    pub p: &'a MstCcProblem<W>,
+    pub seed: Seed,
+    pub params: Vec<(&'static str, Json)>,
+    pub elapsed: f64,
+    pub num_conflicts: u32,
+    pub weight: W,
+    pub obj: W,
+    pub tree: &'a [Edge<StaticGraph>],
+}
+
+impl<'a, W: Weight> Report<'a, W> {
+    // Writes the header (if the format has one) and the row of the report
+    pub fn write<O: Write>(&self, format: OutputFormat, out: &mut O) -> io::Result<()> {
+        write_header(format, &self.params, out)?;
+        self.write_row(format, out)
+    }
+
+    pub fn write_row<O: Write>(&self, format: OutputFormat, out: &mut O) -> io::Result<()> {
+        match format {
+            OutputFormat::Text => self.write_text(out),
+            OutputFormat::Csv => self.write_csv_row(out),
+            OutputFormat::Json => writeln!(out, "{}", self.to_json()),
+        }
+    }
+
+    pub fn write_text<O: Write>(&self, out: &mut O) -> io::Result<()> {
+        write!(
+            out,
+            "{},{:.02},{},{},",
+            self.p.name, self.elapsed, self.num_conflicts, self.weight
+        )?;
+        for (u, v) in self.p.g.ends(self.tree) {
+            write!(out, "{}-{} ", u, v)?;
+        }
+        writeln!(out)
+    }
+
+    pub fn write_csv_row<O: Write>(&self, out: &mut O) -> io::Result<()> {
+        write!(out, "{},{},", csv_field(&self.p.name), self.seed)?;
+        for (_, value) in &self.params {
+            write!(out, "{},", csv_field(&value.to_plain_string()))?;
+        }
+        write!(
+            out,
+            "{:.02},{},{},{},",
+            self.elapsed, self.num_conflicts, self.weight, self.obj
+        )?;
+        for (i, (u, v)) in self.p.g.ends(self.tree).enumerate() {
+            if i != 0 {
+                write!(out, " ")?;
+            }
+            write!(out, "{}-{}", u, v)?;
+        }
+        writeln!(out)
+    }
+
+    pub fn to_json(&self) -> Json {
+        let g = &self.p.g;
+        let ends = |e| {
+            let (u, v) = g.ends(e);
+            Json::from(vec![u, v])
+        };
+        let conflicts = TrackConflicts::with_edges(self.p, self.tree);
+        let pairs = conflicts
+            .pairs()
+            .into_iter()
+            .map(|(e, f)| Json::from(vec![ends(e), ends(f)]))
+            .collect::<Vec<_>>();
+        Json::object()
+            .with("name", &*self.p.name)
+            .with("seed", self.seed.value())
+            .with(
+                "params",
+                Json::Object(
+                    self.params
+                        .iter()
+                        .map(|&(k, ref v)| (k.into(), v.clone()))
+                        .collect(),
+                ),
+            )
+            .with("time", self.elapsed)
+            .with("conflicts", self.num_conflicts)
+            .with("weight", self.weight)
+            .with("obj", self.obj)
+            .with(
+                "edges",
+                self.tree.iter().map(|&e| ends(e)).collect::<Vec<_>>(),
+            )
+            .with("conflicting_pairs", pairs)
+    }
+}
@@ -0,0 +1,567 @@
+// system
+use std::mem;
+
+// external
+use fera::fun::vec;
+use fera::graph::algs::Kruskal;
+use fera::graph::prelude::*;
+use fera::graph::unionfind::{UnionFind, WithUnionFind};
+
+// local
+use {Checkpoint, MstCcProblem, RngVersion, StreamRng, TrackConflicts};
+
+// A perturbation of the ils. The strength is the number of edges changed in the tree (the
+// ils num_excludes), at most the number of edges of the tree.
+pub trait Perturbation {
+    // Called at the start of each ils run
+    fn start(&mut self) {}
+
+    fn perturb(&mut self, tree: &mut Vec<Edge<StaticGraph>>, strength: u32, rng: &mut StreamRng);
+
+    // Saves the state that is kept between the perturbations (like an order of the edges)
+    fn save(&self, _c: &mut Checkpoint) {}
+
+    // Restores the state saved by save, called after start
+    fn restore(&mut self, _c: &Checkpoint) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+impl<P: Perturbation + ?Sized> Perturbation for Box<P> {
+    fn start(&mut self) {
+        (**self).start()
+    }
+
+    fn perturb(&mut self, tree: &mut Vec<Edge<StaticGraph>>, strength: u32, rng: &mut StreamRng) {
+        (**self).perturb(tree, strength, rng)
+    }
+
+    fn save(&self, c: &mut Checkpoint) {
+        (**self).save(c)
+    }
+
+    fn restore(&mut self, c: &Checkpoint) -> Result<(), String> {
+        (**self).restore(c)
+    }
+}
+
+// Removes random edges of the tree and reconnects it with random edges
+pub struct RandomExclusion<'a> {
+    reconnect: RandomReconnect<'a>,
+    exclude: Vec<Edge<StaticGraph>>,
+}
+
+impl<'a> RandomExclusion<'a> {
+    pub fn new<W>(p: &'a MstCcProblem<W>) -> Self {
+        RandomExclusion {
+            reconnect: RandomReconnect::new(&p.g),
+            exclude: vec![],
+        }
+    }
+}
+
+impl<'a> Perturbation for RandomExclusion<'a> {
+    fn start(&mut self) {
+        // the versions 0 and 1 shuffle the edges in the original order in each run
+        self.reconnect.reset_shuffled();
+    }
+
+    fn perturb(&mut self, tree: &mut Vec<Edge<StaticGraph>>, strength: u32, rng: &mut StreamRng) {
+        self.exclude.clear();
+        for _ in 0..strength.min(tree.len() as u32) {
+            let i = rng.gen_index(tree.len());
+            self.exclude.push(tree.swap_remove(i));
+        }
+        self.reconnect.reconnect(tree, &self.exclude, rng);
+    }
+
+    fn save(&self, c: &mut Checkpoint) {
+        self.reconnect.save(c)
+    }
+
+    fn restore(&mut self, c: &Checkpoint) -> Result<(), String> {
+        self.reconnect.restore(c)
+    }
+}
+
+// Like RandomExclusion, but removes edges that conflict with other edges of the tree first
+pub struct ConflictExclusion<'a> {
+    conflicts: TrackConflicts<'a>,
+    reconnect: RandomReconnect<'a>,
+    exclude: Vec<Edge<StaticGraph>>,
+}
+
+impl<'a> ConflictExclusion<'a> {
+    pub fn new<W>(p: &'a MstCcProblem<W>) -> Self {
+        ConflictExclusion {
+            conflicts: TrackConflicts::new(p),
+            reconnect: RandomReconnect::new(&p.g),
+            exclude: vec![],
+        }
+    }
+}
+
+impl<'a> Perturbation for ConflictExclusion<'a> {
+    fn perturb(&mut self, tree: &mut Vec<Edge<StaticGraph>>, strength: u32, rng: &mut StreamRng) {
+        self.conflicts.reset();
+        self.conflicts.add_edges(&*tree);
+        self.exclude.clear();
+        for _ in 0..strength.min(tree.len() as u32) {
+            let conflicts = &self.conflicts;
+            let num = tree.iter().filter(|&&e| conflicts[e] != 0).count();
+            let i = if num == 0 {
+                rng.gen_index(tree.len())
+            } else {
+                let k = rng.gen_index(num);
+                tree.iter()
+                    .enumerate()
+                    .filter(|&(_, &e)| conflicts[e] != 0)
+                    .nth(k)
+                    .unwrap()
+                    .0
+            };
+            let e = tree.swap_remove(i);
+            self.conflicts.remove_edge(e);
+            self.exclude.push(e);
+        }
+        self.reconnect.reconnect(tree, &self.exclude, rng);
+    }
+
+    fn save(&self, c: &mut Checkpoint) {
+        self.reconnect.save(c)
+    }
+
+    fn restore(&mut self, c: &Checkpoint) -> Result<(), String> {
+        self.reconnect.restore(c)
+    }
+}
+
+// Removes the edges of a random connected subtree and reconnects the tree with random edges
+pub struct SubtreeExclusion<'a, W: 'a> {
+    p: &'a MstCcProblem<W>,
+    in_subtree: DefaultVertexPropMut<StaticGraph, bool>,
+    reconnect: RandomReconnect<'a>,
+    exclude: Vec<Edge<StaticGraph>>,
+}
+
+impl<'a, W> SubtreeExclusion<'a, W> {
+    pub fn new(p: &'a MstCcProblem<W>) -> Self {
+        SubtreeExclusion {
+            p,
+            in_subtree: p.g.default_vertex_prop(false),
+            reconnect: RandomReconnect::new(&p.g),
+            exclude: vec![],
+        }
+    }
+}
+
+impl<'a, W> Perturbation for SubtreeExclusion<'a, W> {
+    fn perturb(&mut self, tree: &mut Vec<Edge<StaticGraph>>, strength: u32, rng: &mut StreamRng) {
+        if strength == 0 || tree.is_empty() {
+            return;
+        }
+        let g = &self.p.g;
+        let in_subtree = &mut self.in_subtree;
+        in_subtree.set_values(g.vertices(), false);
+        self.exclude.clear();
+        // the subtree grows by removing a random tree edge incident to it
+        let e = tree.swap_remove(rng.gen_index(tree.len()));
+        let (u, v) = g.ends(e);
+        in_subtree[u] = true;
+        in_subtree[v] = true;
+        self.exclude.push(e);
+        while self.exclude.len() < strength as usize {
+            let num = tree
+                .iter()
+                .filter(|&&e| in_subtree[g.source(e)] || in_subtree[g.target(e)])
+                .count();
+            if num == 0 {
+                break;
+            }
+            let k = rng.gen_index(num);
+            let i = tree
+                .iter()
+                .enumerate()
+                .filter(|&(_, &e)| in_subtree[g.source(e)] || in_subtree[g.target(e)])
+                .nth(k)
+                .unwrap()
+                .0;
+            let e = tree.swap_remove(i);
+            let (u, v) = g.ends(e);
+            in_subtree[u] = true;
+            in_subtree[v] = true;
+            self.exclude.push(e);
+        }
+        self.reconnect.reconnect(tree, &self.exclude, rng);
+    }
+
+    fn save(&self, c: &mut Checkpoint) {
+        self.reconnect.save(c)
+    }
+
+    fn restore(&mut self, c: &Checkpoint) -> Result<(), String> {
+        self.reconnect.restore(c)
+    }
+}
+
+// Makes strength random edge replacements, ignoring the objective function
+pub struct RandomWalk<'a, W: 'a> {
+    p: &'a MstCcProblem<W>,
+    in_tree: DefaultEdgePropMut<StaticGraph, bool>,
+    // tree edge used to reach each vertex in the search of a path
+    parent: DefaultVertexPropMut<StaticGraph, Option<Edge<StaticGraph>>>,
+    adj: DefaultVertexPropMut<StaticGraph, Vec<Edge<StaticGraph>>>,
+    edges: Vec<Edge<StaticGraph>>,
+}
+
+impl<'a, W> RandomWalk<'a, W> {
+    pub fn new(p: &'a MstCcProblem<W>) -> Self {
+        RandomWalk {
+            p,
+            in_tree: p.g.default_edge_prop(false),
+            parent: p.g.default_vertex_prop(None),
+            adj: p.g.default_vertex_prop(vec![]),
+            edges: vec(p.g.edges()),
+        }
+    }
+
+    // Returns the edges of the path in the tree from u to v
+    fn path(&mut self, u: Vertex<StaticGraph>, v: Vertex<StaticGraph>) -> Vec<Edge<StaticGraph>> {
+        let g = &self.p.g;
+        self.parent.set_values(g.vertices(), None);
+        let mut stack = vec![u];
+        while let Some(x) = stack.pop() {
+            if x == v {
+                break;
+            }
+            for &e in &self.adj[x] {
+                let y = g.opposite(x, e);
+                if y != u && self.parent[y].is_none() {
+                    self.parent[y] = Some(e);
+                    stack.push(y);
+                }
+            }
+        }
+        let mut path = vec![];
+        let mut x = v;
+        while let Some(e) = self.parent[x] {
+            path.push(e);
+            x = g.opposite(x, e);
+        }
+        path
+    }
+}
+
+impl<'a, W> Perturbation for RandomWalk<'a, W> {
+    fn perturb(&mut self, tree: &mut Vec<Edge<StaticGraph>>, strength: u32, rng: &mut StreamRng) {
+        let g = &self.p.g;
+        if tree.len() == g.num_edges() {
+            // there is no edge to replace
+            return;
+        }
+        self.in_tree.set_values(g.edges(), false);
+        self.in_tree.set_values(tree.iter().cloned(), true);
+        for u in g.vertices() {
+            self.adj[u].clear();
+        }
+        for &e in &*tree {
+            let (u, v) = g.ends(e);
+            self.adj[u].push(e);
+            self.adj[v].push(e);
+        }
+
+        for _ in 0..strength {
+            let add = loop {
+                let e = self.edges[rng.gen_index(self.edges.len())];
+                if !self.in_tree[e] {
+                    break e;
+                }
+            };
+            let (u, v) = g.ends(add);
+            let path = self.path(u, v);
+            let rem = path[rng.gen_index(path.len())];
+
+            let i = tree.iter().position(|&e| e == rem).unwrap();
+            tree[i] = add;
+            self.in_tree[rem] = false;
+            self.in_tree[add] = true;
+            let (a, b) = g.ends(rem);
+            self.adj[a].retain(|&e| e != rem);
+            self.adj[b].retain(|&e| e != rem);
+            self.adj[u].push(add);
+            self.adj[v].push(add);
+        }
+    }
+}
+
+// Removes pairs of conflicting edges of the tree (random pairs if there are no conflicts) and
+// reconnects the tree preferring the edges that conflict with fewer edges of the remaining tree.
+// Each pair is a double bridge, there are strength / 2 (rounded up) of them.
+pub struct ConflictDoubleBridge<'a, W: 'a> {
+    p: &'a MstCcProblem<W>,
+    conflicts: TrackConflicts<'a>,
+    // the order of the Kruskal of the versions 0 and 1
+    edges: Vec<Edge<StaticGraph>>,
+    exclude: Vec<Edge<StaticGraph>>,
+    tmp: Vec<Edge<StaticGraph>>,
+    // groups[k] are the edges that conflict with k edges of the tree (version 2)
+    groups: Vec<Vec<Edge<StaticGraph>>>,
+    ds: UnionFind<StaticGraph>,
+}
+
+impl<'a, W> ConflictDoubleBridge<'a, W> {
+    pub fn new(p: &'a MstCcProblem<W>) -> Self {
+        ConflictDoubleBridge {
+            p,
+            conflicts: TrackConflicts::new(p),
+            edges: vec(p.g.edges()),
+            exclude: vec![],
+            tmp: vec![],
+            groups: vec![],
+            ds: p.g.new_unionfind(),
+        }
+    }
+
+    // Same distribution of the Kruskal on the shuffled edges stably sorted by the number of
+    // conflicts, but the edges of each group are sampled (without replacement) only until the
+    // forest is connected
+    fn reconnect_sampling(&mut self, tree: &mut Vec<Edge<StaticGraph>>, rng: &mut StreamRng) {
+        let g = &self.p.g;
+        for group in &mut self.groups {
+            group.clear();
+        }
+        for e in g.edges() {
+            let k = self.conflicts[e] as usize;
+            if k >= self.groups.len() {
+                self.groups.resize(k + 1, vec![]);
+            }
+            self.groups[k].push(e);
+        }
+        self.ds.reset(g);
+        for &e in &*tree {
+            let (u, v) = g.ends(e);
+            self.ds.union(u, v);
+        }
+        // the excluded edges are used only if the graph without them is not connected
+        for &use_excluded in &[false, true] {
+            for group in &mut self.groups {
+                let mut end = group.len();
+                while end != 0 && tree.len() + 1 < g.num_vertices() {
+                    let i = rng.gen_index(end);
+                    end -= 1;
+                    group.swap(i, end);
+                    let e = group[end];
+                    if !use_excluded && self.exclude.contains(&e) {
+                        continue;
+                    }
+                    let (u, v) = g.ends(e);
+                    if !self.ds.in_same_set(u, v) {
+                        self.ds.union(u, v);
+                        tree.push(e);
+                    }
+                }
+            }
+        }
+        assert_eq!(
+            g.num_vertices() - 1,
+            tree.len(),
+            "the graph is not connected"
+        );
+        self.exclude.clear();
+    }
+}
+
+impl<'a, W> Perturbation for ConflictDoubleBridge<'a, W> {
+    fn perturb(&mut self, tree: &mut Vec<Edge<StaticGraph>>, strength: u32, rng: &mut StreamRng) {
+        self.conflicts.reset();
+        self.conflicts.add_edges(&*tree);
+        self.exclude.clear();
+        for _ in 0..strength / 2 + strength % 2 {
+            if tree.len() < 2 {
+                break;
+            }
+            let pairs = self.conflicts.pairs();
+            let (e, f) = if pairs.is_empty() {
+                let i = rng.gen_index(tree.len());
+                let mut j = rng.gen_index(tree.len() - 1);
+                if j >= i {
+                    j += 1;
+                }
+                (tree[i], tree[j])
+            } else {
+                pairs[rng.gen_index(pairs.len())]
+            };
+            for &e in &[e, f] {
+                let i = tree.iter().position(|&x| x == e).unwrap();
+                tree.swap_remove(i);
+                self.conflicts.remove_edge(e);
+                self.exclude.push(e);
+            }
+        }
+        if rng.version() == RngVersion::V2 {
+            return self.reconnect_sampling(tree, rng);
+        }
+        let conflicts = &self.conflicts;
+        reconnect(
+            &self.p.g,
+            tree,
+            &mut self.edges,
+            &mut self.exclude,
+            &mut self.tmp,
+            |edges| {
+                rng.shuffle(edges);
+                edges.sort_by_key(|&e| conflicts[e]);
+            },
+        );
+    }
+
+    fn save(&self, c: &mut Checkpoint) {
+        c.set_edges(&self.p.g, "perturbation_edges", &self.edges);
+    }
+
+    fn restore(&mut self, c: &Checkpoint) -> Result<(), String> {
+        self.edges = restore_edges(&self.p.g, c, "perturbation_edges")?;
+        Ok(())
+    }
+}
+
+// Reconnects a forest to a spanning tree adding edges that join distinct components. The result has
+// the same distribution of a Kruskal on a random permutation of the edges. Since the rng version 2
+// the edges are sampled (without replacement) only until the forest is connected, the previous
+// versions shuffle all of them.
+pub struct RandomReconnect<'a> {
+    g: &'a StaticGraph,
+    // edges[..end] are the edges not sampled yet in a reconnection
+    edges: Vec<Edge<StaticGraph>>,
+    ds: UnionFind<StaticGraph>,
+    // the order of the Kruskal of the versions 0 and 1
+    shuffled: Vec<Edge<StaticGraph>>,
+    exclude: Vec<Edge<StaticGraph>>,
+    tmp: Vec<Edge<StaticGraph>>,
+}
+
+impl<'a> RandomReconnect<'a> {
+    pub fn new(g: &'a StaticGraph) -> Self {
+        RandomReconnect {
+            g,
+            edges: vec(g.edges()),
+            ds: g.new_unionfind(),
+            shuffled: vec(g.edges()),
+            exclude: vec![],
+            tmp: vec![],
+        }
+    }
+
+    // Restores the original order of the edges shuffled by the versions 0 and 1
+    pub fn reset_shuffled(&mut self) {
+        self.shuffled.clear();
+        self.shuffled.extend(self.g.edges());
+    }
+
+    // Adds edges that are not in exclude to the forest tree until it is a spanning tree. If the
+    // graph without the excluded edges is not connected, the excluded edges are also used.
+    pub fn reconnect(
+        &mut self,
+        tree: &mut Vec<Edge<StaticGraph>>,
+        exclude: &[Edge<StaticGraph>],
+        rng: &mut StreamRng,
+    ) {
+        let g = self.g;
+        if rng.version() != RngVersion::V2 {
+            self.exclude.clear();
+            self.exclude.extend_from_slice(exclude);
+            reconnect(
+                g,
+                tree,
+                &mut self.shuffled,
+                &mut self.exclude,
+                &mut self.tmp,
+                |edges| rng.shuffle(edges),
+            );
+            return;
+        }
+        if tree.len() + 1 >= g.num_vertices() {
+            return;
+        }
+        self.ds.reset(g);
+        for &e in &*tree {
+            let (u, v) = g.ends(e);
+            self.ds.union(u, v);
+        }
+        let mut use_excluded = false;
+        let mut end = self.edges.len();
+        while tree.len() + 1 < g.num_vertices() {
+            if end == 0 {
+                assert!(!use_excluded, "the graph is not connected");
+                use_excluded = true;
+                end = self.edges.len();
+            }
+            let i = rng.gen_index(end);
+            end -= 1;
+            self.edges.swap(i, end);
+            let e = self.edges[end];
+            if !use_excluded && exclude.contains(&e) {
+                continue;
+            }
+            let (u, v) = g.ends(e);
+            if !self.ds.in_same_set(u, v) {
+                self.ds.union(u, v);
+                tree.push(e);
+            }
+        }
+    }
+
+    // The order of the edges changes in each reconnection, so it is part of the state of the
+    // perturbations
+    pub fn save(&self, c: &mut Checkpoint) {
+        c.set_edges(self.g, "perturbation_edges", &self.edges);
+        c.set_edges(self.g, "perturbation_shuffled", &self.shuffled);
+    }
+
+    pub fn restore(&mut self, c: &Checkpoint) -> Result<(), String> {
+        self.edges = restore_edges(self.g, c, "perturbation_edges")?;
+        self.shuffled = restore_edges(self.g, c, "perturbation_shuffled")?;
+        Ok(())
+    }
+}
+
+// The order of all edges saved as key
+fn restore_edges(
+    g: &StaticGraph,
+    c: &Checkpoint,
+    key: &str,
+) -> Result<Vec<Edge<StaticGraph>>, String> {
+    let edges = c.get_edges(g, key)?;
+    if edges.len() != g.num_edges() {
+        return Err(format!("{}: invalid number of edges", key));
+    }
+    Ok(edges)
+}
+
+// Reconnects the forest tree to a spanning tree with a Kruskal on the edges ordered by order,
+// ignoring the edges in exclude. If the graph without the excluded edges is not connected, the
+// excluded edges are used. Used only by the rng versions 0 and 1, the version 2 samples the edges.
+fn reconnect<F>(
+    g: &StaticGraph,
+    tree: &mut Vec<Edge<StaticGraph>>,
+    edges: &mut Vec<Edge<StaticGraph>>,
+    exclude: &mut Vec<Edge<StaticGraph>>,
+    tmp: &mut Vec<Edge<StaticGraph>>,
+    mut order: F,
+) where
+    F: FnMut(&mut Vec<Edge<StaticGraph>>),
+{
+    tmp.clear();
+    while tmp.len() != g.num_vertices() - 1 {
+        tmp.clear();
+        order(edges);
+        {
+            let edges = tree
+                .iter()
+                .chain(edges.iter().filter(|e| !exclude.contains(*e)));
+            tmp.extend(g.kruskal().edges(edges));
+        }
+        exclude.clear();
+    }
+    mem::swap(tree, tmp);
+}
@@ -0,0 +1,585 @@
+// system
+use std::cell::RefCell;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Instant;
+
+// external
+use fera::fun::vec;
+use fera::graph::algs::Kruskal;
+use fera::graph::prelude::*;
+
+// local
+use {
+    new_grasp, new_greedy, new_prim_greedy, Acceptance, CancelToken, Checkpoint,
+    ConflictDoubleBridge, ConflictExclusion, Grasp, Ils, IlsCheckpoint, LogObserver,
+    MatroidIntersection, MstCcProblem, Observer, OneEdgeReplacement, Perturbation, RandomExclusion,
+    RandomWalk, Rcl, RngVersion, Seed, Stream, StreamRng, SubtreeExclusion, TrackConflicts,
+    TwoEdgeReplacement, Weight,
+};
+
+// The method used to create the initial solution (and the restart solutions)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Init {
+    Random,
+    Kruskal,
+    Greedy,
+    PrimGreedy,
+}
+
+impl Init {
+    pub const ALL: [Init; 4] = [Init::Random, Init::Kruskal, Init::Greedy, Init::PrimGreedy];
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Init::Random => "random",
+            Init::Kruskal => "kruskal",
+            Init::Greedy => "greedy",
+            Init::PrimGreedy => "prim-greedy",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Algorithm {
+    TwoEx,
+    FourEx,
+    TwoExFourEx,
+    IlsTwoEx,
+    IlsFourEx,
+    IlsTwoExFourEx,
+    GraspTwoEx,
+    GraspFourEx,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 8] = [
+        Algorithm::TwoEx,
+        Algorithm::FourEx,
+        Algorithm::TwoExFourEx,
+        Algorithm::IlsTwoEx,
+        Algorithm::IlsFourEx,
+        Algorithm::IlsTwoExFourEx,
+        Algorithm::GraspTwoEx,
+        Algorithm::GraspFourEx,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Algorithm::TwoEx => "2ex",
+            Algorithm::FourEx => "4ex",
+            Algorithm::TwoExFourEx => "2ex-4ex",
+            Algorithm::IlsTwoEx => "ils-2ex",
+            Algorithm::IlsFourEx => "ils-4ex",
+            Algorithm::IlsTwoExFourEx => "ils-2ex-4ex",
+            Algorithm::GraspTwoEx => "grasp-2ex",
+            Algorithm::GraspFourEx => "grasp-4ex",
+        }
+    }
+}
+
+// The perturbation of the ils
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PerturbationKind {
+    Random,
+    Conflicts,
+    Subtree,
+    RandomWalk,
+    DoubleBridge,
+}
+
+impl PerturbationKind {
+    pub const ALL: [PerturbationKind; 5] = [
+        PerturbationKind::Random,
+        PerturbationKind::Conflicts,
+        PerturbationKind::Subtree,
+        PerturbationKind::RandomWalk,
+        PerturbationKind::DoubleBridge,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            PerturbationKind::Random => "random",
+            PerturbationKind::Conflicts => "conflicts",
+            PerturbationKind::Subtree => "subtree",
+            PerturbationKind::RandomWalk => "random-walk",
+            PerturbationKind::DoubleBridge => "double-bridge",
+        }
+    }
+}
+
+macro_rules! impl_from_str_display {
+    ($t:ident, $what:expr) => {
+        impl FromStr for $t {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                $t::ALL
+                    .iter()
+                    .cloned()
+                    .find(|x| x.name() == s)
+                    .ok_or_else(|| format!("invalid {} {}", $what, s))
+            }
+        }
+
+        impl fmt::Display for $t {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self.name())
+            }
+        }
+    };
+}
+
+impl_from_str_display!(Init, "init");
+impl_from_str_display!(Algorithm, "algorithm");
+impl_from_str_display!(PerturbationKind, "perturbation");
+
+// The parameters of solve, the default values are the defaults of the solve subcommand
+#[derive(Clone, Debug)]
+pub struct SolverConfig {
+    pub seed: Seed,
+    pub rng_version: RngVersion,
+    pub alpha: u32,
+    pub beta: u32,
+    // alpha and beta used by the greedy and grasp constructions
+    pub greedy_alpha: u32,
+    pub greedy_beta: u32,
+    pub sort: bool,
+    pub stop_on_feasible: bool,
+    // do not use the exact algorithms, Kruskal when there are no conflicts and matroid
+    // intersection when the conflicts are disjoint cliques (they are also not used with an
+    // initial tree, checkpoints or stop_on_feasible)
+    pub no_exact: bool,
+    pub two_phase: bool,
+    // None means the number of edges of the instance
+    pub ils_max_iters: Option<u32>,
+    pub ils_max_iters_no_improv: u32,
+    pub ils_excludes: u32,
+    pub ils_perturbation: PerturbationKind,
+    pub ils_acceptance: Acceptance,
+    pub ils_restart: u32,
+    pub ils_restart_to_best: u32,
+    pub grasp_iters: u32,
+    pub grasp_rcl: Rcl,
+    pub init: Init,
+    // the initial solution, used instead of init (which is still used for the restarts)
+    pub initial: Option<Vec<Edge<StaticGraph>>>,
+    pub alg: Algorithm,
+    // stops the search, the clones of the token can be cancelled from other threads
+    pub cancel: CancelToken,
+    // the file where the state of the ils is saved at the start of every checkpoint_every
+    // iterations (only ils-2ex and ils-4ex without two phases can be saved)
+    pub checkpoint: Option<String>,
+    pub checkpoint_every: u32,
+    // the saved state from which the ils continues
+    pub resume: Option<Checkpoint>,
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        SolverConfig {
+            seed: Seed::new_random(),
+            rng_version: RngVersion::LATEST,
+            alpha: 1,
+            beta: 10_000,
+            greedy_alpha: 1,
+            greedy_beta: 10_000,
+            sort: false,
+            stop_on_feasible: false,
+            no_exact: false,
+            two_phase: false,
+            ils_max_iters: Some(1000),
+            ils_max_iters_no_improv: 1_000_000_000,
+            ils_excludes: 1,
+            ils_perturbation: PerturbationKind::Random,
+            ils_acceptance: Acceptance::RandomWalk,
+            ils_restart: 1_000_000_000,
+            ils_restart_to_best: 1_000_000_000,
+            grasp_iters: 100,
+            grasp_rcl: Rcl::Value(0.1),
+            init: Init::Random,
+            initial: None,
+            alg: Algorithm::IlsTwoEx,
+            cancel: CancelToken::new(),
+            checkpoint: None,
+            checkpoint_every: 100,
+            resume: None,
+        }
+    }
+}
+
+impl SolverConfig {
+    // Returns an error if the checkpoints are used with an algorithm that can not be saved or if
+    // resume was saved by a run with another instance or other parameters
+    pub fn check_checkpoint<W: Weight>(&self, p: &MstCcProblem<W>) -> Result<(), String> {
+        if self.checkpoint.is_none() && self.resume.is_none() {
+            return Ok(());
+        }
+        match self.alg {
+            Algorithm::IlsTwoEx | Algorithm::IlsFourEx if !self.two_phase => (),
+            _ => {
+                return Err(
+                    "only ils-2ex and ils-4ex without two phases can use checkpoints".into(),
+                )
+            }
+        }
+        if self.rng_version == RngVersion::V0 {
+            return Err("the rng version 0 can not be saved in a checkpoint".into());
+        }
+        if let Some(ref c) = self.resume {
+            for (key, value) in self.checkpoint_header(p) {
+                let saved: String = c.get(key)?;
+                if saved != value {
+                    return Err(format!(
+                        "the checkpoint was saved with {} {} (not {})",
+                        key, saved, value
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // The instance and the parameters that must be the same to resume a run
+    fn checkpoint_header<W: Weight>(&self, p: &MstCcProblem<W>) -> Vec<(&'static str, String)> {
+        let initial = match self.initial {
+            Some(ref tree) => {
+                let index = p.g.edge_index();
+                let edges: Vec<_> = tree.iter().map(|&e| index.get(e).to_string()).collect();
+                edges.join(" ")
+            }
+            None => "none".into(),
+        };
+        vec![
+            ("instance", p.name.clone()),
+            ("vertices", p.g.num_vertices().to_string()),
+            ("edges", p.g.num_edges().to_string()),
+            ("weights", W::NAME.into()),
+            ("seed", self.seed.to_string()),
+            ("rng_version", self.rng_version.value().to_string()),
+            ("init", self.init.to_string()),
+            ("initial", initial),
+            ("alg", self.alg.to_string()),
+            ("alpha", self.alpha.to_string()),
+            ("beta", self.beta.to_string()),
+            ("greedy_alpha", self.greedy_alpha.to_string()),
+            ("greedy_beta", self.greedy_beta.to_string()),
+            ("sort", self.sort.to_string()),
+            ("stop_on_feasible", self.stop_on_feasible.to_string()),
+            (
+                "ils_max_iters_no_improv",
+                self.ils_max_iters_no_improv.to_string(),
+            ),
+            ("ils_excludes", self.ils_excludes.to_string()),
+            ("ils_perturbation", self.ils_perturbation.to_string()),
+            ("ils_acceptance", self.ils_acceptance.to_string()),
+            ("ils_restart", self.ils_restart.to_string()),
+            ("ils_restart_to_best", self.ils_restart_to_best.to_string()),
+        ]
+    }
+}
+
+pub struct Solution<W = u32> {
+    pub tree: Vec<Edge<StaticGraph>>,
+    pub weight: W,
+    pub num_conflicts: u32,
+    // the objective with the alpha and beta of the config
+    pub obj: W,
+    pub conflicting_pairs: Vec<(Edge<StaticGraph>, Edge<StaticGraph>)>,
+    // the running time in seconds
+    pub elapsed: f64,
+    // true if the solution was found by the exact algorithm (so it is optimal)
+    pub exact: bool,
+}
+
+impl<W> Solution<W> {
+    pub fn is_feasible(&self) -> bool {
+        self.num_conflicts == 0
+    }
+}
+
+// Runs the algorithm of config on p, like the solve subcommand. The alpha and beta of p are set
+// to the values of config. Panics if the checkpoint options are invalid (see check_checkpoint).
+pub fn solve<W: Weight>(p: &MstCcProblem<W>, config: &SolverConfig) -> Solution<W> {
+    solve_observed(p, config, &LogObserver)
+}
+
+// Like solve, but the events of the searches are sent to observer (instead of logged)
+pub fn solve_observed<W: Weight>(
+    p: &MstCcProblem<W>,
+    config: &SolverConfig,
+    observer: &dyn Observer<W>,
+) -> Solution<W> {
+    let start = Instant::now();
+    let (tree, num_conflicts, exact) = run(p, config, observer);
+    let elapsed = start.elapsed();
+    let elapsed = elapsed.as_secs() as f64 + f64::from(elapsed.subsec_nanos()) / 1_000_000_000.0;
+
+    let weight = p.weight(&tree);
+    let conflicting_pairs = TrackConflicts::with_edges(p, &tree).pairs();
+    Solution {
+        obj: p.obj(weight, num_conflicts),
+        tree,
+        weight,
+        num_conflicts,
+        conflicting_pairs,
+        elapsed,
+        exact,
+    }
+}
+
+// Returns the solution, its number of conflicts and if it was found by the exact algorithm
+fn run<W: Weight>(
+    p: &MstCcProblem<W>,
+    config: &SolverConfig,
+    observer: &dyn Observer<W>,
+) -> (Vec<Edge<StaticGraph>>, u32, bool) {
+    let seed = config.seed;
+    info!("Using {:?} and {:?}", seed, config.rng_version);
+    if let Err(e) = config.check_checkpoint(p) {
+        panic!("{}", e);
+    }
+
+    p.alpha.set(config.alpha);
+    p.beta.set(config.beta);
+
+    // the exact algorithms ignore the initial tree, the checkpoints and stop_on_feasible
+    let use_exact = !config.no_exact
+        && config.initial.is_none()
+        && config.resume.is_none()
+        && config.checkpoint.is_none()
+        && !config.stop_on_feasible;
+    if use_exact && p.num_cc == 0 {
+        info!("There are no conflicts, using Kruskal");
+        return (p.mst(), 0, true);
+    }
+    if use_exact {
+        if let Some(exact) = MatroidIntersection::new(p) {
+            info!("The conflicts are disjoint cliques, using matroid intersection");
+            match exact.solve() {
+                Ok(tree) => return (tree, 0, true),
+                Err(forest) => info!(
+                    "The instance is infeasible, the largest conflict free forest has {} edges",
+                    forest.len()
+                ),
+            }
+        }
+    }
+
+    // the rng and the order of the edges of the random restarts, shared with the checkpoints
+    let restart = &RefCell::new((
+        seed.new_rng(config.rng_version, Stream::Init),
+        vec(p.g.edges()),
+    ));
+    let build = |tree: &mut Vec<_>| {
+        let (ref mut rng, ref mut edges) = *restart.borrow_mut();
+        let (alpha, beta) = (p.alpha.get(), p.beta.get());
+        p.alpha.set(config.greedy_alpha);
+        p.beta.set(config.greedy_beta);
+
+        tree.clear();
+        match config.init {
+            Init::Random => {
+                rng.shuffle(edges);
+                tree.extend(p.g.kruskal().edges(&*edges));
+            }
+            Init::Kruskal => tree.extend(p.mst()),
+            Init::Greedy => new_greedy(p, tree),
+            Init::PrimGreedy => new_prim_greedy(p, tree),
+        };
+
+        p.alpha.set(alpha);
+        p.beta.set(beta);
+    };
+
+    let mut tree = if let Some(ref initial) = config.initial {
+        initial.clone()
+    } else {
+        let mut tree = vec![];
+        build(&mut tree);
+        tree
+    };
+
+    if let Some(ref c) = config.resume {
+        let restore = || -> Result<_, String> {
+            Ok((
+                c.get_rng("restart_rng")?,
+                c.get_edges(&p.g, "restart_edges")?,
+            ))
+        };
+        *restart.borrow_mut() = restore().unwrap_or_else(|e| panic!("invalid checkpoint: {}", e));
+    }
+
+    let mut rng = seed.new_rng(config.rng_version, Stream::Perturbation);
+
+    let mut ils = Ils {
+        p,
+        max_iters: config
+            .ils_max_iters
+            .unwrap_or_else(|| p.g.num_edges() as u32),
+        max_iters_no_improv: config.ils_max_iters_no_improv,
+        num_excludes: config.ils_excludes,
+        iters_restart: config.ils_restart,
+        iters_restart_to_best: config.ils_restart_to_best,
+        restart: build,
+        perturbation: match config.ils_perturbation {
+            PerturbationKind::Random => Box::new(RandomExclusion::new(p)) as Box<dyn Perturbation>,
+            PerturbationKind::Conflicts => Box::new(ConflictExclusion::new(p)),
+            PerturbationKind::Subtree => Box::new(SubtreeExclusion::new(p)),
+            PerturbationKind::RandomWalk => Box::new(RandomWalk::new(p)),
+            PerturbationKind::DoubleBridge => Box::new(ConflictDoubleBridge::new(p)),
+        },
+        acceptance: config.ils_acceptance,
+        stop_on_feasible: config.stop_on_feasible,
+        observer,
+        cancel: config.cancel.clone(),
+        checkpoint: config.checkpoint.as_ref().map(|file| IlsCheckpoint {
+            every: config.checkpoint_every,
+            save: Box::new(move |c: &mut Checkpoint| {
+                for (key, value) in config.checkpoint_header(p) {
+                    c.set(key, value);
+                }
+                let (ref rng, ref edges) = *restart.borrow();
+                c.set_rng("restart_rng", rng);
+                c.set_edges(&p.g, "restart_edges", edges);
+                match c.write(file) {
+                    Ok(()) => debug!("Checkpoint saved to {}", file),
+                    Err(e) => warn!("Checkpoint not saved to {}: {}", file, e),
+                }
+            }),
+        }),
+    };
+
+    let mut grasp = Grasp {
+        p,
+        max_iters: config.grasp_iters,
+        construct: |tree: &mut Vec<_>, rng: &mut StreamRng| {
+            let (alpha, beta) = (p.alpha.get(), p.beta.get());
+            p.alpha.set(config.greedy_alpha);
+            p.beta.set(config.greedy_beta);
+            new_grasp(p, tree, config.grasp_rcl, rng);
+            p.alpha.set(alpha);
+            p.beta.set(beta);
+        },
+        stop_on_feasible: config.stop_on_feasible,
+        observer,
+        cancel: config.cancel.clone(),
+    };
+
+    let mut one = OneEdgeReplacement::new(p);
+    one.sort = config.sort;
+    one.stop_on_feasible = config.stop_on_feasible;
+    one.observer = observer;
+    one.cancel = config.cancel.clone();
+
+    let mut two = TwoEdgeReplacement::new(p);
+    two.sort = config.sort;
+    two.stop_on_feasible = config.stop_on_feasible;
+    two.observer = observer;
+    two.cancel = config.cancel.clone();
+
+    let mut run_alg =
+        |tree: &mut Vec<_>, one: &mut OneEdgeReplacement<W>, two: &mut TwoEdgeReplacement<W>| {
+            ils.stop_on_feasible = one.stop_on_feasible;
+            grasp.stop_on_feasible = one.stop_on_feasible;
+            // in phase two the solutions with conflicts are replaced by the last feasible solution
+            let feasible_only = one.feasible_only;
+            let mut last = if feasible_only { tree.clone() } else { vec![] };
+            let mut keep = |tree: &mut Vec<_>, conflicts: u32| {
+                if feasible_only {
+                    if conflicts == 0 {
+                        last.clone_from(tree);
+                    } else {
+                        tree.clone_from(&last);
+                        return 0;
+                    }
+                }
+                conflicts
+            };
+            match config.alg {
+                Algorithm::TwoEx => one.run(tree),
+                Algorithm::FourEx => two.run(tree),
+                Algorithm::TwoExFourEx => {
+                    one.run(tree);
+                    two.run(tree)
+                }
+                Algorithm::IlsTwoEx => run_ils(&mut ils, config, tree, &mut rng, |tree| {
+                    let c = one.run(tree);
+                    keep(tree, c)
+                }),
+                Algorithm::IlsFourEx => run_ils(&mut ils, config, tree, &mut rng, |tree| {
+                    let c = two.run(tree);
+                    keep(tree, c)
+                }),
+                Algorithm::IlsTwoExFourEx => {
+                    ils.run(tree, &mut rng, |tree| {
+                        let c = one.run(tree);
+                        keep(tree, c)
+                    });
+                    ils.run(tree, &mut rng, |tree| {
+                        let c = two.run(tree);
+                        keep(tree, c)
+                    })
+                }
+                Algorithm::GraspTwoEx => grasp.run(tree, &mut rng, |tree| {
+                    let c = one.run(tree);
+                    keep(tree, c)
+                }),
+                Algorithm::GraspFourEx => grasp.run(tree, &mut rng, |tree| {
+                    let c = two.run(tree);
+                    keep(tree, c)
+                }),
+            }
+        };
+
+    if !config.two_phase {
+        let conflicts = run_alg(&mut tree, &mut one, &mut two);
+        return (tree, conflicts, false);
+    }
+
+    info!("Phase one: minimizing the number of conflicts");
+    p.alpha.set(0);
+    p.beta.set(1);
+    one.only_conflicting = true;
+    one.stop_on_feasible = true;
+    two.only_conflicting = true;
+    two.stop_on_feasible = true;
+    let conflicts = run_alg(&mut tree, &mut one, &mut two);
+    p.alpha.set(config.alpha);
+    p.beta.set(config.beta);
+    if conflicts != 0 || config.stop_on_feasible || config.cancel.is_cancelled() {
+        return (tree, conflicts, false);
+    }
+
+    info!("Phase two: minimizing the weight without leaving the feasible region");
+    one.only_conflicting = false;
+    one.stop_on_feasible = false;
+    one.feasible_only = true;
+    two.only_conflicting = false;
+    two.stop_on_feasible = false;
+    two.feasible_only = true;
+    let conflicts = run_alg(&mut tree, &mut one, &mut two);
+    (tree, conflicts, false)
+}
+
+// Runs the ils or, if config.resume is set, continues the saved run
+fn run_ils<W, R, P, F>(
+    ils: &mut Ils<W, R, P>,
+    config: &SolverConfig,
+    tree: &mut Vec<Edge<StaticGraph>>,
+    rng: &mut StreamRng,
+    local_search: F,
+) -> u32
+where
+    W: Weight,
+    R: FnMut(&mut Vec<Edge<StaticGraph>>),
+    P: Perturbation,
+    F: FnMut(&mut Vec<Edge<StaticGraph>>) -> u32,
+{
+    match config.resume {
+        Some(ref c) => ils
+            .resume(c, tree, rng, local_search)
+            .unwrap_or_else(|e| panic!("invalid checkpoint: {}", e)),
+        None => ils.run(tree, rng, local_search),
+    }
+}
@@ -0,0 +1,140 @@
+// system
+use std::collections::VecDeque;
+use std::fmt;
+
+// external
+use fera::graph::prelude::*;
+
+// local
+use {bridges, weight_lower_bound, Json, MstCcProblem, TrackConflicts, Weight};
+
+pub struct InstanceStats<W = u32> {
+    pub name: String,
+    pub n: usize,
+    pub m: usize,
+    // number of conflicting pairs of edges
+    pub num_cc: usize,
+    // conflict_degrees[d] is the number of edges that conflicts with exactly d edges
+    pub conflict_degrees: Vec<usize>,
+    pub density: f64,
+    pub conflict_density: f64,
+    pub num_bridges: usize,
+    // weight and number of conflicts of a minimum spanning tree that ignores the conflicts
+    pub mst_weight: W,
+    pub mst_conflicts: u32,
+    // number of connected components of the conflict graph, ignoring edges without conflicts
+    pub conflict_components: usize,
+    pub conflict_bipartite: bool,
+}
+
+impl<W: Weight> InstanceStats<W> {
+    pub fn new(p: &MstCcProblem<W>) -> Self {
+        let g = &p.g;
+        let n = g.num_vertices();
+        let m = g.num_edges();
+
+        let mut conflict_degrees = vec![];
+        for e in g.edges() {
+            let d = p.cc[e].len();
+            if d >= conflict_degrees.len() {
+                conflict_degrees.resize(d + 1, 0);
+            }
+            conflict_degrees[d] += 1;
+        }
+
+        let mst = TrackConflicts::with_edges(p, p.mst());
+        let (conflict_components, conflict_bipartite) = conflict_graph_components(p);
+
+        InstanceStats {
+            name: p.name.clone(),
+            n,
+            m,
+            num_cc: p.num_cc,
+            conflict_degrees,
+            density: density(n, m),
+            conflict_density: density(m, p.num_cc),
+            num_bridges: bridges(g).len(),
+            mst_weight: weight_lower_bound(p),
+            mst_conflicts: mst.total(),
+            conflict_components,
+            conflict_bipartite,
+        }
+    }
+
+    pub fn to_json(&self) -> Json {
+        Json::object()
+            .with("name", &*self.name)
+            .with("n", self.n)
+            .with("m", self.m)
+            .with("conflicts", self.num_cc)
+            .with("conflict_degrees", self.conflict_degrees.clone())
+            .with("density", self.density)
+            .with("conflict_density", self.conflict_density)
+            .with("bridges", self.num_bridges)
+            .with("mst_weight", self.mst_weight)
+            .with("mst_conflicts", self.mst_conflicts)
+            .with("conflict_components", self.conflict_components)
+            .with("conflict_bipartite", self.conflict_bipartite)
+    }
+}
+
+impl<W: Weight> fmt::Display for InstanceStats<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "name: {}", self.name)?;
+        writeln!(f, "n: {}", self.n)?;
+        writeln!(f, "m: {}", self.m)?;
+        writeln!(f, "conflicts: {}", self.num_cc)?;
+        write!(f, "conflict degrees (degree:count):")?;
+        for (d, &count) in self.conflict_degrees.iter().enumerate() {
+            if count != 0 {
+                write!(f, " {}:{}", d, count)?;
+            }
+        }
+        writeln!(f)?;
+        writeln!(f, "density: {:.04}", self.density)?;
+        writeln!(f, "conflict density: {:.04}", self.conflict_density)?;
+        writeln!(f, "bridges: {}", self.num_bridges)?;
+        writeln!(f, "mst weight: {}", self.mst_weight)?;
+        writeln!(f, "mst conflicts: {}", self.mst_conflicts)?;
+        writeln!(f, "conflict components: {}", self.conflict_components)?;
+        writeln!(f, "conflict bipartite: {}", self.conflict_bipartite)
+    }
+}
+
+// density of a graph with n vertices and m edges
+fn density(n: usize, m: usize) -> f64 {
+    if n < 2 {
+        0.0
+    } else {
+        2.0 * m as f64 / (n as f64 * (n as f64 - 1.0))
+    }
+}
+
+// Returns the number of connected components of the conflict graph (ignoring edges without
+// conflicts) and if the conflict graph is bipartite
+fn conflict_graph_components<W>(p: &MstCcProblem<W>) -> (usize, bool) {
+    const NONE: u8 = 2;
+    let mut side = p.g.default_edge_prop(NONE);
+    let mut queue = VecDeque::new();
+    let mut comps = 0;
+    let mut bipartite = true;
+    for e in p.g.edges() {
+        if side[e] != NONE || p.cc[e].is_empty() {
+            continue;
+        }
+        comps += 1;
+        side[e] = 0;
+        queue.push_back(e);
+        while let Some(e) = queue.pop_front() {
+            for &f in &p.cc[e] {
+                if side[f] == NONE {
+                    side[f] = 1 - side[e];
+                    queue.push_back(f);
+                } else if side[f] == side[e] {
+                    bipartite = false;
+                }
+            }
+        }
+    }
+    (comps, bipartite)
+}
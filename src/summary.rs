@@ -0,0 +1,300 @@
+// system
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+
+// local
+use {csv_field, split_csv_line};
+
+// The result of one run of the solver
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunResult {
+    // the experiment configuration name, empty if the results are not from an experiment
+    pub config: String,
+    pub name: String,
+    pub time: f64,
+    pub conflicts: u32,
+    pub weight: f64,
+}
+
+// Aggregated results of the runs on one instance
+#[derive(Clone, Debug, PartialEq)]
+pub struct InstanceSummary {
+    pub config: String,
+    pub name: String,
+    pub samples: usize,
+    pub median_weight: f64,
+    pub best_weight: f64,
+    pub best_weight_freq: usize,
+    pub std_weight: f64,
+    pub median_time: f64,
+    pub total_time: f64,
+    pub min_conflicts: u32,
+    pub min_conflicts_freq: usize,
+    // fraction of the runs that found a solution without conflicts
+    pub success_rate: f64,
+}
+
+// Reads the results of a csv file written by the solver. The columns are found by the header
+// names (name or instance, time, conflicts, weight or obj and the optional config), case
+// insensitive.
+pub fn read_results(file: &str) -> Result<Vec<RunResult>, Box<dyn Error>> {
+    let mut lines = BufReader::new(File::open(file)?).lines();
+    let header = match lines.next() {
+        Some(header) => header?.to_lowercase(),
+        None => return Ok(vec![]),
+    };
+    let header = split_csv_line(&header);
+    let header = header.iter().map(|h| h.trim()).collect::<Vec<_>>();
+    let column = |names: &[&str]| {
+        names
+            .iter()
+            .filter_map(|name| header.iter().position(|h| h == name))
+            .next()
+            .ok_or_else(|| format!("{}: missing column {}", file, names.join(" or ")))
+    };
+    let name = column(&["name", "instance"])?;
+    let time = column(&["time"])?;
+    let conflicts = column(&["conflicts"])?;
+    let weight = column(&["weight", "obj"])?;
+    let config = column(&["config"]).ok();
+
+    let mut results = vec![];
+    for line in lines {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields = split_csv_line(&line);
+        let fields = fields.iter().map(|f| f.trim()).collect::<Vec<_>>();
+        let field = |i: usize| {
+            fields
+                .get(i)
+                .cloned()
+                .ok_or_else(|| format!("{}: missing field in line {}", file, line))
+        };
+        results.push(RunResult {
+            config: match config {
+                Some(config) => field(config)?.into(),
+                None => String::new(),
+            },
+            name: field(name)?.into(),
+            time: field(time)?.parse()?,
+            conflicts: field(conflicts)?.parse()?,
+            weight: field(weight)?.parse()?,
+        });
+    }
+    Ok(results)
+}
+
+// Groups the results by configuration (in order of appearance) and instance (in natural order)
+pub fn summarize(results: &[RunResult]) -> Vec<InstanceSummary> {
+    let mut configs: Vec<&str> = vec![];
+    for r in results {
+        if !configs.contains(&&*r.config) {
+            configs.push(&r.config);
+        }
+    }
+    let mut summaries = vec![];
+    for config in configs {
+        let mut names = results
+            .iter()
+            .filter(|r| r.config == config)
+            .map(|r| r.name.as_str())
+            .collect::<Vec<_>>();
+        names.sort_by(|a, b| natural_cmp(a, b));
+        names.dedup();
+        for name in names {
+            let runs = results
+                .iter()
+                .filter(|r| r.config == config && r.name == name)
+                .collect::<Vec<_>>();
+            summaries.push(summarize_instance(config, name, &runs));
+        }
+    }
+    summaries
+}
+
+fn summarize_instance(config: &str, name: &str, runs: &[&RunResult]) -> InstanceSummary {
+    let weights = runs.iter().map(|r| r.weight).collect::<Vec<_>>();
+    let times = runs.iter().map(|r| r.time).collect::<Vec<_>>();
+    let best_weight = weights.iter().cloned().fold(f64::INFINITY, f64::min);
+    let min_conflicts = runs.iter().map(|r| r.conflicts).min().unwrap_or(0);
+    let count = |pred: &dyn Fn(&RunResult) -> bool| runs.iter().filter(|r| pred(r)).count();
+    InstanceSummary {
+        config: config.into(),
+        name: name.into(),
+        samples: runs.len(),
+        median_weight: median(&weights),
+        best_weight,
+        best_weight_freq: count(&|r| r.weight == best_weight),
+        std_weight: std_dev(&weights),
+        median_time: median(&times),
+        total_time: times.iter().sum(),
+        min_conflicts,
+        min_conflicts_freq: count(&|r| r.conflicts == min_conflicts),
+        success_rate: count(&|r| r.conflicts == 0) as f64 / runs.len() as f64,
+    }
+}
+
+const HEADER: [&str; 12] = [
+    "Config",
+    "Instance",
+    "Sam",
+    "Obj",
+    "Best",
+    "Freq",
+    "Std",
+    "Time (s)",
+    "Total Time (s)",
+    "Min Conf",
+    "Freq",
+    "Success",
+];
+
+fn row(s: &InstanceSummary) -> Vec<String> {
+    vec![
+        s.config.clone(),
+        s.name.clone(),
+        s.samples.to_string(),
+        format!("{}", s.median_weight.round()),
+        format!("{}", s.best_weight),
+        s.best_weight_freq.to_string(),
+        format!("{:.03}", s.std_weight),
+        format!("{:.03}", s.median_time),
+        format!("{:.03}", s.total_time),
+        s.min_conflicts.to_string(),
+        s.min_conflicts_freq.to_string(),
+        format!("{:.03}", s.success_rate),
+    ]
+}
+
+// Writes an aligned table like the one produced by tabulate. The config column is omitted if
+// the results are not from an experiment.
+pub fn write_summary_table<W: Write>(summaries: &[InstanceSummary], out: &mut W) -> io::Result<()> {
+    let skip = if summaries.iter().all(|s| s.config.is_empty()) {
+        1
+    } else {
+        0
+    };
+    let header = &HEADER[skip..];
+    let rows = summaries
+        .iter()
+        .map(|s| row(s).split_off(skip))
+        .collect::<Vec<_>>();
+    let widths = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .map(|r| r[i].len())
+                .chain(Some(header[i].len()))
+                .max()
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let write_row = |out: &mut W, row: &[&str]| {
+        for (i, (value, &width)) in row.iter().zip(&widths).enumerate() {
+            if i == 0 {
+                write!(out, "{:<width$}", value, width = width)?;
+            } else if i == 1 && skip == 0 {
+                write!(out, "  {:<width$}", value, width = width)?;
+            } else {
+                write!(out, "  {:>width$}", value, width = width)?;
+            }
+        }
+        writeln!(out)
+    };
+
+    write_row(out, header)?;
+    let sep = widths.iter().map(|&w| "-".repeat(w)).collect::<Vec<_>>();
+    write_row(out, &sep.iter().map(String::as_str).collect::<Vec<_>>())?;
+    for r in &rows {
+        write_row(out, &r.iter().map(String::as_str).collect::<Vec<_>>())?;
+    }
+    Ok(())
+}
+
+pub fn write_summary_csv<W: Write>(summaries: &[InstanceSummary], out: &mut W) -> io::Result<()> {
+    writeln!(
+        out,
+        "config,instance,samples,median_weight,best_weight,best_weight_freq,std_weight,\
+         median_time,total_time,min_conflicts,min_conflicts_freq,success_rate"
+    )?;
+    for s in summaries {
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{},{},{}",
+            csv_field(&s.config),
+            csv_field(&s.name),
+            s.samples,
+            s.median_weight,
+            s.best_weight,
+            s.best_weight_freq,
+            s.std_weight,
+            s.median_time,
+            s.total_time,
+            s.min_conflicts,
+            s.min_conflicts_freq,
+            s.success_rate
+        )?;
+    }
+    Ok(())
+}
+
+fn median(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mut values = values.to_vec();
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    let n = values.len();
+    if n % 2 == 1 {
+        values[n / 2]
+    } else {
+        (values[n / 2 - 1] + values[n / 2]) / 2.0
+    }
+}
+
+// Population standard deviation (like numpy.std)
+fn std_dev(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    (values.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n).sqrt()
+}
+
+// Compares strings treating runs of digits as numbers, so z50-200 < z100-300
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a, b);
+    loop {
+        match (a.chars().next(), b.chars().next()) {
+            (None, None) => return Ordering::Equal,
+            (None, _) => return Ordering::Less,
+            (_, None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let i = a.find(|c: char| !c.is_ascii_digit()).unwrap_or(a.len());
+                let j = b.find(|c: char| !c.is_ascii_digit()).unwrap_or(b.len());
+                let (x, y) = (
+                    a[..i].trim_start_matches('0'),
+                    b[..j].trim_start_matches('0'),
+                );
+                let ord = x.len().cmp(&y.len()).then_with(|| x.cmp(y));
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+                a = &a[i..];
+                b = &b[j..];
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+                a = &a[x.len_utf8()..];
+                b = &b[y.len_utf8()..];
+            }
+        }
+    }
+}
@@ -0,0 +1,272 @@
+// system
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+// local
+use {Json, StreamRng};
+
+// Racing of candidate configurations (like the F-Race used by irace). In each step all the alive
+// candidates are evaluated on a new block (an instance and a seed) and, after first_test blocks, a
+// Friedman test is used to discard the candidates that are statistically worse than the best one.
+pub struct Race<C> {
+    alive: Vec<usize>,
+    // costs[b][j] is the cost of the candidate alive[j] in the block b
+    costs: Vec<Vec<C>>,
+    pub first_test: usize,
+    // significance level of the tests
+    pub alpha: f64,
+}
+
+impl<C: PartialOrd + Clone> Race<C> {
+    pub fn new(num_candidates: usize) -> Self {
+        Race {
+            alive: (0..num_candidates).collect(),
+            costs: vec![],
+            first_test: 5,
+            alpha: 0.05,
+        }
+    }
+
+    pub fn alive(&self) -> &[usize] {
+        &self.alive
+    }
+
+    pub fn num_blocks(&self) -> usize {
+        self.costs.len()
+    }
+
+    // Adds a block with the costs of the alive candidates (in the order of alive) and returns the
+    // discarded candidates
+    pub fn add_block(&mut self, costs: Vec<C>) -> Vec<usize> {
+        assert_eq!(self.alive.len(), costs.len());
+        self.costs.push(costs);
+        if self.num_blocks() < self.first_test || self.alive.len() < 2 {
+            return vec![];
+        }
+
+        let keep = self.friedman_test();
+        let discarded = self
+            .alive
+            .iter()
+            .zip(&keep)
+            .filter(|&(_, &keep)| !keep)
+            .map(|(&i, _)| i)
+            .collect();
+        self.alive = retain(&self.alive, &keep);
+        for block in &mut self.costs {
+            *block = retain(block, &keep);
+        }
+        discarded
+    }
+
+    // The alive candidates and its mean ranks, sorted by mean rank
+    pub fn ranking(&self) -> Vec<(usize, f64)> {
+        let b = self.num_blocks().max(1) as f64;
+        let mut ranking = self
+            .alive
+            .iter()
+            .cloned()
+            .zip(self.rank_sums().into_iter().map(|r| r / b))
+            .collect::<Vec<_>>();
+        ranking.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal));
+        ranking
+    }
+
+    pub fn best(&self) -> usize {
+        self.ranking()[0].0
+    }
+
+    fn rank_sums(&self) -> Vec<f64> {
+        let mut sums = vec![0.0; self.alive.len()];
+        for block in &self.costs {
+            for (sum, r) in sums.iter_mut().zip(ranks(block)) {
+                *sum += r;
+            }
+        }
+        sums
+    }
+
+    // Friedman test followed by the post-hoc comparisons with the best candidate, as described by
+    // Conover (Practical Nonparametric Statistics). Returns which alive candidates must be kept.
+    fn friedman_test(&self) -> Vec<bool> {
+        let k = self.alive.len() as f64;
+        let b = self.num_blocks() as f64;
+        let sums = self.rank_sums();
+        let a: f64 = self
+            .costs
+            .iter()
+            .flat_map(|block| ranks(block))
+            .map(|r| r * r)
+            .sum();
+        let c = b * k * (k + 1.0) * (k + 1.0) / 4.0;
+        if a - c <= 1e-9 {
+            // all candidates tied in all blocks
+            return vec![true; self.alive.len()];
+        }
+
+        let t = (k - 1.0)
+            * sums
+                .iter()
+                .map(|r| (r - b * (k + 1.0) / 2.0).powi(2))
+                .sum::<f64>()
+            / (a - c);
+        if t <= chi_squared_quantile(1.0 - self.alpha, k - 1.0) {
+            return vec![true; self.alive.len()];
+        }
+
+        let df = (b - 1.0) * (k - 1.0);
+        let sum_sq = sums.iter().map(|r| r * r).sum::<f64>();
+        let diff =
+            student_t_quantile(1.0 - self.alpha / 2.0, df) * (2.0 * (b * a - sum_sq) / df).sqrt();
+        let best = sums.iter().cloned().fold(f64::INFINITY, f64::min);
+        sums.iter().map(|&r| r - best <= diff).collect()
+    }
+}
+
+fn retain<T: Clone>(values: &[T], keep: &[bool]) -> Vec<T> {
+    values
+        .iter()
+        .zip(keep)
+        .filter(|&(_, &keep)| keep)
+        .map(|(v, _)| v.clone())
+        .collect()
+}
+
+// Ranks starting from 1, ties get the average rank
+fn ranks<C: PartialOrd>(costs: &[C]) -> Vec<f64> {
+    let mut order = (0..costs.len()).collect::<Vec<_>>();
+    order.sort_by(|&i, &j| costs[i].partial_cmp(&costs[j]).unwrap_or(Ordering::Equal));
+    let mut ranks = vec![0.0; costs.len()];
+    let mut i = 0;
+    while i < order.len() {
+        let mut j = i + 1;
+        while j < order.len() && costs[order[j]] == costs[order[i]] {
+            j += 1;
+        }
+        // positions i..j are tied
+        let rank = (i + j + 1) as f64 / 2.0;
+        for &k in &order[i..j] {
+            ranks[k] = rank;
+        }
+        i = j;
+    }
+    ranks
+}
+
+// Acklam's approximation of the inverse of the standard normal distribution function
+fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e+01,
+        2.209460984245205e+02,
+        -2.759285104469687e+02,
+        1.38357751867269e+02,
+        -3.066479806614716e+01,
+        2.506628277459239e+00,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e+01,
+        1.615858368580409e+02,
+        -1.556989798598866e+02,
+        6.680131188771972e+01,
+        -1.328068155288572e+01,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-03,
+        -3.223964580411365e-01,
+        -2.400758277161838e+00,
+        -2.549732539343734e+00,
+        4.374664141464968e+00,
+        2.938163982698783e+00,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-03,
+        3.224671290700398e-01,
+        2.445134137142996e+00,
+        3.754408661907416e+00,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < P_LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p <= 1.0 - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    }
+}
+
+// Exact for 1 and 2 degrees of freedom, Wilson-Hilferty approximation otherwise
+fn chi_squared_quantile(p: f64, df: f64) -> f64 {
+    if df == 1.0 {
+        normal_quantile((1.0 + p) / 2.0).powi(2)
+    } else if df == 2.0 {
+        -2.0 * (1.0 - p).ln()
+    } else {
+        let h = 2.0 / (9.0 * df);
+        df * (1.0 - h + normal_quantile(p) * h.sqrt()).powi(3)
+    }
+}
+
+// Cornish-Fisher expansion (Abramowitz and Stegun 26.7.5)
+fn student_t_quantile(p: f64, df: f64) -> f64 {
+    let z = normal_quantile(p);
+    let z2 = z * z;
+    let g1 = (z2 + 1.0) * z / 4.0;
+    let g2 = ((5.0 * z2 + 16.0) * z2 + 3.0) * z / 96.0;
+    let g3 = (((3.0 * z2 + 19.0) * z2 + 17.0) * z2 - 15.0) * z / 384.0;
+    let g4 = ((((79.0 * z2 + 776.0) * z2 + 1482.0) * z2 - 1920.0) * z2 - 945.0) * z / 92160.0;
+    z + g1 / df + g2 / df.powi(2) + g3 / df.powi(3) + g4 / df.powi(4)
+}
+
+// Returns all the combinations of the values of the parameters, or max randomly chosen distinct
+// combinations if there are more than max
+pub fn sample_configurations(
+    params: &[(String, Vec<Json>)],
+    max: usize,
+    rng: &mut StreamRng,
+) -> Vec<Vec<(String, Json)>> {
+    let total = params
+        .iter()
+        .fold(1usize, |total, p| total.saturating_mul(p.1.len()));
+    let config = |choices: &[usize]| {
+        params
+            .iter()
+            .zip(choices)
+            .map(|((key, values), &i)| (key.clone(), values[i].clone()))
+            .collect()
+    };
+    if total <= max {
+        return (0..total)
+            .map(|mut index| {
+                let choices = params
+                    .iter()
+                    .map(|p| {
+                        let i = index % p.1.len();
+                        index /= p.1.len();
+                        i
+                    })
+                    .collect::<Vec<_>>();
+                config(&choices)
+            })
+            .collect();
+    }
+    let mut seen = HashSet::new();
+    let mut configs = vec![];
+    while configs.len() < max {
+        let choices = params
+            .iter()
+            .map(|p| rng.gen_index(p.1.len()))
+            .collect::<Vec<_>>();
+        if seen.insert(choices.clone()) {
+            configs.push(config(&choices));
+        }
+    }
+    configs
+}
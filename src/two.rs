@@ -4,44 +4,56 @@ use std::mem;
 // external
 use fera::graph::algs::Trees;
 use fera::graph::prelude::*;
-use fera::graph::props::FnProp;
 use fera::graph::sum_prop;
 
 // local
-use {log_improvement, MstCcProblem, TrackConflicts, TrackConnectivity2};
+use {
+    sort_by_weight, CancelToken, Cost, LogObserver, MstCcProblem, Observer, TrackConflicts,
+    TrackConnectivity2, Weight,
+};
 
-pub struct TwoEdgeReplacement<'a> {
-    p: &'a MstCcProblem,
+pub struct TwoEdgeReplacement<'a, W: 'a> {
+    p: &'a MstCcProblem<W>,
     in_tree: DefaultEdgePropMut<StaticGraph, bool>,
     non_tree: Vec<Edge<StaticGraph>>,
     conflicts: TrackConflicts<'a>,
     connectivity: TrackConnectivity2<'a, StaticGraph>,
-    weight: u32,
+    weight: W,
     num_conflicts: u32,
-    obj: u32,
+    obj: W,
     c01: Vec<usize>,
     c02: Vec<usize>,
     c12: Vec<usize>,
     pub sort: bool,
     pub stop_on_feasible: bool,
+    // only replace pairs of tree edges with at least one edge with conflicts
+    pub only_conflicting: bool,
+    // only do replacements that do not increase the number of conflicts
+    pub feasible_only: bool,
+    pub observer: &'a dyn Observer<W>,
+    pub cancel: CancelToken,
 }
 
-impl<'a> TwoEdgeReplacement<'a> {
-    pub fn new(p: &'a MstCcProblem) -> Self {
+impl<'a, W: Weight> TwoEdgeReplacement<'a, W> {
+    pub fn new(p: &'a MstCcProblem<W>) -> Self {
         TwoEdgeReplacement {
             p,
             non_tree: Vec::with_capacity(p.g.num_edges()),
             in_tree: p.g.edge_prop(false),
             conflicts: TrackConflicts::new(p),
             connectivity: TrackConnectivity2::new(&p.g),
-            weight: 0,
+            weight: W::default(),
             num_conflicts: 0,
-            obj: 0,
+            obj: W::default(),
             c01: vec![],
             c02: vec![],
             c12: vec![],
             sort: false,
             stop_on_feasible: false,
+            only_conflicting: false,
+            feasible_only: false,
+            observer: &LogObserver,
+            cancel: CancelToken::new(),
         }
     }
 
@@ -55,6 +67,9 @@ impl<'a> TwoEdgeReplacement<'a> {
             if self.stop_on_feasible && self.conflicts.total() == 0 {
                 break;
             }
+            if self.cancel.is_cancelled() {
+                break;
+            }
             if let Some(ss) = self.two_replacement(tree, s) {
                 s = ss;
             } else {
@@ -62,8 +77,14 @@ impl<'a> TwoEdgeReplacement<'a> {
             }
         }
 
-        let expected_weight: u32 = sum_prop(&self.p.w, &*tree);
-        assert_eq!(expected_weight, self.weight);
+        let expected_weight: W = sum_prop(&self.p.w, &*tree);
+        // the sum depends on the order of the edges if the weights are floating point
+        assert!(
+            expected_weight.approx_eq(self.weight),
+            "{} != {}",
+            expected_weight,
+            self.weight
+        );
 
         debug!("End two-edge-replacement with weight = {}", self.weight);
 
@@ -76,13 +97,23 @@ impl<'a> TwoEdgeReplacement<'a> {
         self.sort(tree);
 
         for i in s..tree.len() {
+            // a scan of the neighborhood can be long, so it is also stopped
+            if self.cancel.is_cancelled() {
+                return None;
+            }
+
             let (ei, a, b) = self.p.g.ends(tree[i]);
+            let conflicting = self.conflicts[ei] > 0;
 
             self.conflicts.remove_edge(ei);
 
             for j in (i + 1)..tree.len() {
                 let (ej, c, d) = self.p.g.ends(tree[j]);
 
+                if self.only_conflicting && !conflicting && self.conflicts[ej] == 0 {
+                    continue;
+                }
+
                 self.conflicts.remove_edge(ej);
                 self.connectivity.disconnect2((a, b), (c, d));
 
@@ -104,9 +135,9 @@ impl<'a> TwoEdgeReplacement<'a> {
         if self.sort {
             let p = &self.p;
             let conflicts = &self.conflicts;
-            let obj = FnProp(|e| p.obj(p.w.get(e), conflicts[e]));
-            self.non_tree.sort_by_prop(&obj);
-            tree.sort_by_prop(&obj);
+            let obj = |e: &Edge<StaticGraph>| p.obj(p.w[*e], conflicts[*e]);
+            sort_by_weight(&mut self.non_tree, &obj);
+            sort_by_weight(tree, &obj);
             tree.reverse();
         }
     }
@@ -207,8 +238,9 @@ impl<'a> TwoEdgeReplacement<'a> {
             let w = self.p.w.get(ei) + self.p.w.get(ej);
             let c = self.conflicts[ei] + self.conflicts[ej] + 2;
             let key = self.p.obj(w, c);
-            match self.non_tree
-                .binary_search_by_key(&key, |e| self.obj_edge(*e))
+            match self
+                .non_tree
+                .binary_search_by(|e| self.obj_edge(*e).cmp_weight(&key))
             {
                 Ok(to) | Err(to) => to,
             }
@@ -254,7 +286,8 @@ impl<'a> TwoEdgeReplacement<'a> {
                 let new_weight = self.weight - w.get(ei) - w.get(ej) + w.get(ek) + w.get(el);
                 let new_obj = self.p.obj(new_weight, self.conflicts.total());
 
-                if new_obj < self.obj {
+                let feasible = !self.feasible_only || self.conflicts.total() <= self.num_conflicts;
+                if new_obj.improves(self.obj) && feasible {
                     return Some((k, l));
                 }
 
@@ -281,8 +314,15 @@ impl<'a> TwoEdgeReplacement<'a> {
         let num_conflicts = self.conflicts.total();
         let weight = sum_prop(&self.p.w, &*tree);
 
-        log_improvement("conflicts", self.num_conflicts, num_conflicts);
-        log_improvement("weight   ", self.weight, weight);
+        let old = Cost {
+            num_conflicts: self.num_conflicts,
+            weight: self.weight,
+        };
+        let new = Cost {
+            num_conflicts,
+            weight,
+        };
+        self.observer.on_move("4ex", old, new);
 
         self.num_conflicts = num_conflicts;
         self.weight = weight;
@@ -290,7 +330,7 @@ impl<'a> TwoEdgeReplacement<'a> {
     }
 
     fn setup(&mut self, tree: &[Edge<StaticGraph>]) {
-        self.connectivity.set_edges(&*tree);
+        self.connectivity.set_edges(tree);
 
         self.conflicts.reset();
         self.conflicts.add_edges(tree);
@@ -304,7 +344,7 @@ impl<'a> TwoEdgeReplacement<'a> {
         let g = &self.p.g;
         self.non_tree.extend(g.edges().filter(|e| !in_tree[*e]));
 
-        self.weight = sum_prop(&self.p.w, &*tree);
+        self.weight = sum_prop(&self.p.w, tree);
         self.num_conflicts = self.conflicts.total();
         self.obj = self.p.obj(self.weight, self.num_conflicts);
     }
@@ -315,7 +355,7 @@ impl<'a> TwoEdgeReplacement<'a> {
         assert!(g.spanning_subgraph(self.conflicts.edges()).is_tree());
     }
 
-    fn obj_edge(&self, e: Edge<StaticGraph>) -> u32 {
+    fn obj_edge(&self, e: Edge<StaticGraph>) -> W {
         self.p.obj(self.p.w.get(e), self.conflicts[e])
     }
 }
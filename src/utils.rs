@@ -1,4 +1,5 @@
 // system
+use std::fmt;
 use std::num::ParseIntError;
 use std::str::FromStr;
 
@@ -6,6 +7,9 @@ use std::str::FromStr;
 use clap::Arg;
 use rand::{self, Rng, SeedableRng, XorShiftRng};
 
+// local
+use Weight;
+
 pub fn partition<T, F>(xs: &mut [T], mut pred: F) -> usize
 where
     F: FnMut(&T) -> bool,
@@ -41,7 +45,7 @@ pub fn init_logger(level: &str) {
     }
 }
 
-pub fn log_improvement(target: &str, old: u32, new: u32) {
+pub fn log_improvement<W: Weight>(target: &str, old: W, new: W) {
     debug!(
         target: target,
         "{} -> {} ({:.02}%)",
@@ -51,7 +55,7 @@ pub fn log_improvement(target: &str, old: u32, new: u32) {
     );
 }
 
-pub fn log_improvement_best(target: &str, old: u32, new: u32) {
+pub fn log_improvement_best<W: Weight>(target: &str, old: W, new: W) {
     info!(
         target: target,
         "best {} -> {} ({:.02}%)",
@@ -61,13 +65,12 @@ pub fn log_improvement_best(target: &str, old: u32, new: u32) {
     );
 }
 
-fn improvement_percentage(old: u32, new: u32) -> f64 {
-    if old == 0 {
+fn improvement_percentage<W: Weight>(old: W, new: W) -> f64 {
+    let (old, new) = (old.to_f64(), new.to_f64());
+    if old == 0.0 {
         0.0
-    } else if old > new {
-        100.0 * f64::from(old - new) / f64::from(old)
     } else {
-        -100.0 * f64::from(new - old) / f64::from(old)
+        100.0 * (old - new) / old
     }
 }
 
@@ -84,15 +87,236 @@ impl FromStr for Seed {
     }
 }
 
+impl From<u32> for Seed {
+    fn from(value: u32) -> Self {
+        Seed(value)
+    }
+}
+
+impl fmt::Display for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 impl Seed {
     pub fn new_random() -> Seed {
         Seed(rand::weak_rng().gen())
     }
 
+    pub fn value(&self) -> u32 {
+        self.0
+    }
+
     pub fn new_xor_shift_rng(&self) -> XorShiftRng {
         let s = self.0;
         XorShiftRng::from_seed([s, s.wrapping_add(1), s.wrapping_add(2), s.wrapping_add(3)])
     }
+
+    pub fn new_rng(&self, version: RngVersion, stream: Stream) -> StreamRng {
+        match version {
+            // the same generator was used for the initial solution and the perturbations
+            RngVersion::V0 => match stream {
+                Stream::Worker(i) => {
+                    StreamRng::Legacy(Seed(self.0.wrapping_add(i)).new_xor_shift_rng())
+                }
+                _ => StreamRng::Legacy(self.new_xor_shift_rng()),
+            },
+            RngVersion::V1 | RngVersion::V2 => {
+                let mut sm = SplitMix64(u64::from(self.0));
+                let id = stream.id();
+                let mut rng = Pcg32 {
+                    state: 0,
+                    inc: ((sm.next() ^ id) << 1) | 1,
+                };
+                rng.next_u32();
+                rng.state = rng
+                    .state
+                    .wrapping_add(sm.next() ^ id.wrapping_mul(GOLDEN_GAMMA));
+                rng.next_u32();
+                StreamRng::Pcg32(rng, version)
+            }
+        }
+    }
+}
+
+// Random numbers
+
+// The version of the random number streams. The numbers generated by an existing version must
+// never change, so a new version has to be added for any change in the generation.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RngVersion {
+    // rand 0.4 XorShiftRng, the generator used by the versions before the streams, kept to
+    // reproduce old results (the sequence depends on the rand crate version)
+    V0,
+    // PCG32 seeded with SplitMix64, with a distinct sequence for each stream
+    V1,
+    // the streams of V1, the perturbations reconnect the tree sampling edges without replacement
+    // instead of shuffling all edges
+    V2,
+}
+
+impl RngVersion {
+    pub const LATEST: RngVersion = RngVersion::V2;
+
+    pub fn value(&self) -> u32 {
+        *self as u32
+    }
+}
+
+impl FromStr for RngVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "0" => Ok(RngVersion::V0),
+            "1" => Ok(RngVersion::V1),
+            "2" => Ok(RngVersion::V2),
+            _ => Err(format!("invalid rng version {}", s)),
+        }
+    }
+}
+
+// The named sub-streams derived from a seed
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Stream {
+    // the initial solution and the restart solutions
+    Init,
+    // the perturbations of the ils
+    Perturbation,
+    // the generation of random instances
+    Generate,
+    // the sampling of candidate configurations of the tuner
+    Tune,
+    // the i-th worker thread of a parallel algorithm
+    Worker(u32),
+}
+
+impl Stream {
+    fn id(&self) -> u64 {
+        match *self {
+            Stream::Init => 1,
+            Stream::Perturbation => 2,
+            Stream::Generate => 3,
+            Stream::Tune => 4,
+            Stream::Worker(i) => (1 << 32) | u64::from(i),
+        }
+    }
+}
+
+pub enum StreamRng {
+    Legacy(XorShiftRng),
+    Pcg32(Pcg32, RngVersion),
+}
+
+impl StreamRng {
+    // The legacy streams are used to reproduce the results of the previous versions
+    pub fn is_legacy(&self) -> bool {
+        matches!(*self, StreamRng::Legacy(_))
+    }
+
+    // The version of the stream, which the algorithms that use it may depend on
+    pub fn version(&self) -> RngVersion {
+        match *self {
+            StreamRng::Legacy(_) => RngVersion::V0,
+            StreamRng::Pcg32(_, version) => version,
+        }
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        match *self {
+            StreamRng::Legacy(ref mut rng) => rng.next_u32(),
+            StreamRng::Pcg32(ref mut rng, _) => rng.next_u32(),
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let hi = u64::from(self.next_u32());
+        (hi << 32) | u64::from(self.next_u32())
+    }
+
+    // Returns a uniformly distributed value in [0, 1)
+    pub fn gen_f64(&mut self) -> f64 {
+        match *self {
+            StreamRng::Legacy(ref mut rng) => rng.gen(),
+            StreamRng::Pcg32(..) => (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64,
+        }
+    }
+
+    // Returns a uniformly distributed value in [0, n)
+    pub fn gen_index(&mut self, n: usize) -> usize {
+        assert!(n > 0, "empty range");
+        if let StreamRng::Legacy(ref mut rng) = *self {
+            return rng.gen_range(0, n);
+        }
+        let n = n as u64;
+        // rejects the values that would make the result biased
+        let threshold = n.wrapping_neg() % n;
+        loop {
+            let r = self.next_u64();
+            if r >= threshold {
+                return (r % n) as usize;
+            }
+        }
+    }
+
+    // The state (state, inc) of a PCG32 stream or None for a legacy stream (which state can not be
+    // saved)
+    pub fn state(&self) -> Option<(u64, u64)> {
+        match *self {
+            StreamRng::Legacy(_) => None,
+            StreamRng::Pcg32(ref rng, _) => Some((rng.state, rng.inc)),
+        }
+    }
+
+    // The PCG32 stream of version with the given state, that continues the stream that returned
+    // it
+    pub fn from_state(version: RngVersion, state: u64, inc: u64) -> StreamRng {
+        assert_ne!(RngVersion::V0, version, "a legacy rng has no state");
+        StreamRng::Pcg32(Pcg32 { state, inc }, version)
+    }
+
+    // Fisher-Yates shuffle
+    pub fn shuffle<T>(&mut self, values: &mut [T]) {
+        if let StreamRng::Legacy(ref mut rng) = *self {
+            return rng.shuffle(values);
+        }
+        for i in (1..values.len()).rev() {
+            let j = self.gen_index(i + 1);
+            values.swap(i, j);
+        }
+    }
+}
+
+// PCG XSH RR 64/32 (https://www.pcg-random.org)
+pub struct Pcg32 {
+    state: u64,
+    inc: u64,
+}
+
+impl Pcg32 {
+    fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(self.inc);
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        xorshifted.rotate_right((old >> 59) as u32)
+    }
+}
+
+const GOLDEN_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(GOLDEN_GAMMA);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
 }
 
 pub fn arg_seed() -> Arg<'static, 'static> {
@@ -110,8 +334,8 @@ pub fn arg_log() -> Arg<'static, 'static> {
     Arg::with_name("level")
         .long("log")
         .takes_value(true)
-        .possible_values(&["off", "info", "debug"])
-        .default_value("off")
+        .possible_values(&["off", "warn", "info", "debug"])
+        .default_value("warn")
         .help("Enable logging to stderr")
 }
 
@@ -121,3 +345,11 @@ pub fn arg_input() -> Arg<'static, 'static> {
         .required(true)
         .help("The input file")
 }
+
+pub fn arg_weights() -> Arg<'static, 'static> {
+    Arg::with_name("weights")
+        .long("weights")
+        .possible_values(&["u32", "u64", "i64", "f64"])
+        .default_value("u64")
+        .help("The type of the weights and of the objective function")
+}
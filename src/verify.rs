@@ -0,0 +1,91 @@
+// system
+use std::fmt;
+
+// external
+use fera::graph::algs::Trees;
+use fera::graph::prelude::*;
+use fera::graph::sum_prop;
+
+// local
+use {MstCcProblem, TrackConflicts, Weight};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Violation {
+    InvalidEdge(u32, u32),
+    DuplicatedEdge(u32, u32),
+    NotSpanningTree,
+    Conflict((u32, u32), (u32, u32)),
+}
+
+impl Violation {
+    pub fn is_conflict(&self) -> bool {
+        matches!(*self, Violation::Conflict(..))
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Violation::InvalidEdge(u, v) => write!(f, "{}-{} is not an edge", u, v),
+            Violation::DuplicatedEdge(u, v) => write!(f, "duplicated edge {}-{}", u, v),
+            Violation::NotSpanningTree => write!(f, "not a spanning tree"),
+            Violation::Conflict((a, b), (x, y)) => {
+                write!(f, "conflicting edges: {}-{}, {}-{}", a, b, x, y)
+            }
+        }
+    }
+}
+
+pub struct Verification<W = u32> {
+    // The valid edges of the solution, invalid and duplicated edges are skipped
+    pub tree: Vec<Edge<StaticGraph>>,
+    pub weight: W,
+    pub num_conflicts: u32,
+    pub violations: Vec<Violation>,
+}
+
+impl<W> Verification<W> {
+    pub fn is_feasible(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+pub fn verify<W: Weight>(p: &MstCcProblem<W>, ends: &[(u32, u32)]) -> Verification<W> {
+    let g = &p.g;
+    let n = g.num_vertices() as u32;
+    let mut violations = vec![];
+    let mut tree = vec![];
+    let mut in_tree = g.default_edge_prop(false);
+
+    for &(u, v) in ends {
+        let e = if u < n && v < n {
+            g.get_edge_by_ends(u, v)
+        } else {
+            None
+        };
+        match e {
+            None => violations.push(Violation::InvalidEdge(u, v)),
+            Some(e) if in_tree[e] => violations.push(Violation::DuplicatedEdge(u, v)),
+            Some(e) => {
+                in_tree[e] = true;
+                tree.push(e);
+            }
+        }
+    }
+
+    if ends.len() != tree.len() || !g.spanning_subgraph(&tree).is_tree() {
+        violations.push(Violation::NotSpanningTree);
+    }
+
+    let conflicts = TrackConflicts::with_edges(p, &tree);
+    for (e, f) in conflicts.pairs() {
+        violations.push(Violation::Conflict(g.ends(e), g.ends(f)));
+    }
+
+    Verification {
+        weight: sum_prop(&p.w, &tree),
+        num_conflicts: conflicts.total(),
+        tree,
+        violations,
+    }
+}
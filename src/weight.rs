@@ -0,0 +1,153 @@
+// system
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display};
+use std::iter::Sum;
+use std::ops::{Add, Mul, Sub};
+use std::str::FromStr;
+
+// local
+use Json;
+
+// The type of the edge weights and of the objective function. The values are compared with
+// partial_cmp, so they must not be NaN.
+pub trait Weight:
+    Copy
+    + Default
+    + PartialOrd
+    + Debug
+    + Display
+    + FromStr
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Sum
+    + Into<Json>
+    + Send
+    + Sync
+    + 'static
+{
+    // The name used in the weights option
+    const NAME: &'static str;
+
+    fn from_u32(value: u32) -> Self;
+
+    // A value greater than the objective of any solution
+    fn max_value() -> Self;
+
+    fn to_f64(self) -> f64;
+
+    fn cmp_weight(&self, other: &Self) -> Ordering {
+        self.partial_cmp(other).expect("weights must be comparable")
+    }
+
+    // Returns true if self is smaller than old by more than the rounding errors of the type (the
+    // test used to accept an improving move, so the searches do not cycle on ties)
+    fn improves(self, old: Self) -> bool {
+        self < old
+    }
+
+    // Returns true if self and other are equal up to the rounding errors of the type
+    fn approx_eq(self, other: Self) -> bool {
+        !self.improves(other) && !other.improves(self)
+    }
+
+    // Returns false for the values that can not be used as weights (like NaN)
+    fn is_valid(self) -> bool {
+        true
+    }
+}
+
+macro_rules! impl_weight {
+    ($t:ty, $name:expr, $max:expr) => {
+        impl Weight for $t {
+            const NAME: &'static str = $name;
+
+            fn from_u32(value: u32) -> Self {
+                value as $t
+            }
+
+            fn max_value() -> Self {
+                $max
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        }
+    };
+}
+
+impl_weight!(u32, "u32", u32::MAX);
+impl_weight!(u64, "u64", u64::MAX);
+impl_weight!(i64, "i64", i64::MAX);
+// The relative tolerance of the improvement test of the f64 weights
+pub const F64_TOLERANCE: f64 = 1e-9;
+
+impl Weight for f64 {
+    const NAME: &'static str = "f64";
+
+    fn from_u32(value: u32) -> Self {
+        f64::from(value)
+    }
+
+    fn max_value() -> Self {
+        f64::INFINITY
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn improves(self, old: Self) -> bool {
+        // the scale is the new value because old can be infinite
+        self < old && old - self > F64_TOLERANCE * self.abs().max(1.0)
+    }
+
+    fn is_valid(self) -> bool {
+        self.is_finite()
+    }
+}
+
+// Stable sort of values by a weight key
+pub fn sort_by_weight<T, W, F>(values: &mut [T], mut key: F)
+where
+    W: Weight,
+    F: FnMut(&T) -> W,
+{
+    values.sort_by(|a, b| key(a).cmp_weight(&key(b)))
+}
+
+// The weight types that can be selected in the command line
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum WeightType {
+    U32,
+    U64,
+    I64,
+    F64,
+}
+
+impl FromStr for WeightType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            _ if s == u32::NAME => Ok(WeightType::U32),
+            _ if s == u64::NAME => Ok(WeightType::U64),
+            _ if s == i64::NAME => Ok(WeightType::I64),
+            _ if s == f64::NAME => Ok(WeightType::F64),
+            _ => Err(format!("invalid weight type: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for WeightType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            WeightType::U32 => u32::NAME,
+            WeightType::U64 => u64::NAME,
+            WeightType::I64 => i64::NAME,
+            WeightType::F64 => f64::NAME,
+        };
+        write!(f, "{}", name)
+    }
+}
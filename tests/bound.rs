@@ -0,0 +1,52 @@
+extern crate fera;
+extern crate mstcc;
+extern crate rand;
+
+use fera::graph::algs::Components;
+use fera::graph::prelude::*;
+use mstcc::{
+    bridges, conflicts_lower_bound, new_random_instance, weight_lower_bound, RngVersion, Seed,
+    Stream,
+};
+
+#[test]
+fn bridges_small() {
+    // a triangle 0-1-2 connected to the path 2-3-4 with a parallel edge 3-4
+    let mut b = <StaticGraph as WithBuilder>::Builder::new(5, 6);
+    b.add_edge(0, 1); // e0
+    b.add_edge(1, 2); // e1
+    b.add_edge(0, 2); // e2
+    b.add_edge(2, 3); // e3
+    b.add_edge(3, 4); // e4
+    b.add_edge(3, 4); // e5
+    let (g, _, e) = b.finalize_();
+    assert_eq!(vec![e[3]], bridges(&g));
+}
+
+#[test]
+fn bridges_random() {
+    let mut rng = rand::weak_rng();
+    for n in 1..30 {
+        let g = StaticGraph::new_gn_connected(n, &mut rng);
+        let mut expected = vec![];
+        for e in g.edges() {
+            let sub = g.spanning_subgraph(g.edges().filter(|&f| f != e));
+            if !sub.is_connected() {
+                expected.push(e);
+            }
+        }
+        let mut actual = bridges(&g);
+        actual.sort();
+        assert_eq!(expected, actual, "n = {}", n);
+    }
+}
+
+#[test]
+fn lower_bounds() {
+    let mut rng = Seed::from(3).new_rng(RngVersion::LATEST, Stream::Generate);
+    // a tree, so every edge is a bridge
+    let p = new_random_instance("test", 10, 9, 5, (1, 10), &mut rng);
+    assert_eq!(5, conflicts_lower_bound(&p));
+    let weight: u32 = p.g.edges().map(|e| p.w[e]).sum();
+    assert_eq!(weight, weight_lower_bound(&p));
+}
@@ -0,0 +1,93 @@
+extern crate fera;
+extern crate mstcc;
+
+use fera::fun::vec;
+use fera::graph::prelude::*;
+use mstcc::*;
+
+#[test]
+fn same_as_generated() {
+    let mut rng = Seed::from(3).new_rng(RngVersion::LATEST, Stream::Generate);
+    let p = new_random_instance("z20-50-100", 20, 50, 100, (1, 100), &mut rng);
+    let index = p.g.edge_index();
+
+    let mut b = MstCcProblemBuilder::new("z20-50-100");
+    for _ in p.g.vertices() {
+        b.add_vertex();
+    }
+    for (e, u, v) in p.g.edges_with_ends() {
+        assert_eq!(index.get(e), b.add_edge(u as usize, v as usize, p.w[e]));
+    }
+    for e in p.g.edges() {
+        for &f in &p.cc[e] {
+            b.add_conflict(index.get(e), index.get(f));
+        }
+    }
+    assert_eq!(p.num_cc, b.num_conflicts());
+    let q = b.build().unwrap();
+
+    assert_eq!(p.name, q.name);
+    assert_eq!(p.num_cc, q.num_cc);
+    assert_eq!(vec(p.g.edges_with_ends()), vec(q.g.edges_with_ends()));
+    for e in p.g.edges() {
+        assert_eq!(p.w[e], q.w[e]);
+        let mut a = p.cc[e].clone();
+        let mut b = q.cc[e].clone();
+        a.sort();
+        b.sort();
+        assert_eq!(a, b);
+    }
+}
+
+#[test]
+fn repeated_conflicts() {
+    let mut b = MstCcProblemBuilder::new("triangle");
+    let (u, v, x) = (b.add_vertex(), b.add_vertex(), b.add_vertex());
+    let e = b.add_edge(u, v, 1.5);
+    let f = b.add_edge(v, x, 2.0);
+    let g = b.add_edge(x, u, 0.5);
+    b.add_conflict(e, f);
+    b.add_conflict(f, e);
+    b.add_conflict(e, f);
+    b.add_conflict(g, e);
+    assert_eq!(2, b.num_conflicts());
+
+    let p = b.build().unwrap();
+    let edges = vec(p.g.edges());
+    assert_eq!(2, p.num_cc);
+    assert_eq!(vec![edges[1], edges[2]], p.cc[edges[0]]);
+    assert_eq!(vec![edges[0]], p.cc[edges[1]]);
+    assert_eq!(vec![edges[0]], p.cc[edges[2]]);
+    assert_eq!(0.5, p.weight(p.mst()) - 1.5);
+}
+
+#[test]
+fn invalid() {
+    let build = |edges: &[(usize, usize, f64)], conflicts: &[(usize, usize)]| {
+        let mut b = MstCcProblemBuilder::new("invalid");
+        for _ in 0..3 {
+            b.add_vertex();
+        }
+        for &(u, v, w) in edges {
+            b.add_edge(u, v, w);
+        }
+        for &(e, f) in conflicts {
+            b.add_conflict(e, f);
+        }
+        b.build()
+    };
+
+    assert!(build(&[(0, 1, 1.0), (1, 2, 1.0)], &[(0, 1)]).is_ok());
+    // self-loop
+    assert!(build(&[(0, 1, 1.0), (1, 1, 1.0)], &[]).is_err());
+    // parallel edges
+    assert!(build(&[(0, 1, 1.0), (1, 0, 1.0)], &[]).is_err());
+    // invalid vertex
+    assert!(build(&[(0, 1, 1.0), (1, 3, 1.0)], &[]).is_err());
+    // invalid weight
+    assert!(build(&[(0, 1, 1.0), (1, 2, f64::NAN)], &[]).is_err());
+    // self-conflict
+    assert!(build(&[(0, 1, 1.0), (1, 2, 1.0)], &[(1, 1)]).is_err());
+    // invalid edge
+    assert!(build(&[(0, 1, 1.0), (1, 2, 1.0)], &[(0, 2)]).is_err());
+}
@@ -0,0 +1,71 @@
+extern crate fera;
+extern crate mstcc;
+
+mod common;
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use fera::graph::algs::Trees;
+use fera::graph::prelude::*;
+use mstcc::*;
+
+#[test]
+fn clones_share_the_flag() {
+    let token = CancelToken::new();
+    let clone = token.clone();
+    assert!(!token.is_cancelled());
+    clone.cancel();
+    assert!(token.is_cancelled());
+    assert!(!CancelToken::new().is_cancelled());
+}
+
+#[test]
+fn cancelled_local_searches() {
+    let p = common::instance(Seed::from(6), 30, 90, 60);
+    p.beta.set(1000);
+    let mut rng = Seed::from(6).new_rng(RngVersion::V1, Stream::Init);
+    let initial = common::random_tree(&p, &mut rng);
+    let conflicts = TrackConflicts::with_edges(&p, &initial).total();
+
+    let token = CancelToken::new();
+    token.cancel();
+    let mut one = OneEdgeReplacement::new(&p);
+    one.cancel = token.clone();
+    let mut tree = initial.clone();
+    assert_eq!(conflicts, one.run(&mut tree));
+    assert_eq!(initial, tree);
+
+    let mut two = TwoEdgeReplacement::new(&p);
+    two.cancel = token;
+    let mut tree = initial.clone();
+    assert_eq!(conflicts, two.run(&mut tree));
+    assert_eq!(initial, tree);
+}
+
+#[test]
+fn cancel_from_another_thread() {
+    let p = common::instance(Seed::from(6), 60, 200, 300);
+    let token = CancelToken::new();
+    let config = SolverConfig {
+        seed: Seed::from(1),
+        alg: Algorithm::IlsFourEx,
+        ils_max_iters: Some(u32::MAX),
+        cancel: token.clone(),
+        ..SolverConfig::default()
+    };
+
+    let start = Instant::now();
+    let canceller = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(200));
+        token.cancel();
+    });
+    let solution = solve(&p, &config);
+    canceller.join().unwrap();
+
+    assert!(start.elapsed() < Duration::from_secs(60));
+    assert!(p.g.spanning_subgraph(&solution.tree).is_tree());
+    let conflicts = TrackConflicts::with_edges(&p, &solution.tree).total();
+    assert_eq!(conflicts, solution.num_conflicts);
+    assert_eq!(p.weight(&solution.tree), solution.weight);
+}
@@ -0,0 +1,203 @@
+extern crate fera;
+extern crate mstcc;
+
+use std::env;
+
+use fera::fun::vec;
+use fera::graph::prelude::*;
+use mstcc::*;
+
+fn tmp(name: &str) -> String {
+    let path = env::temp_dir().join(format!("mstcc-checkpoint-{}", name));
+    path.to_str().unwrap().into()
+}
+
+#[test]
+fn write_read() {
+    let mut rng = Seed::from(2).new_rng(RngVersion::V1, Stream::Generate);
+    let p = new_random_instance("z10-20-10", 10, 20, 10, (1, 100), &mut rng);
+    let edges = vec(p.g.edges());
+    let mut c = Checkpoint::new();
+    c.set("iter", 10);
+    c.set("iter", 20);
+    c.set("name", "a b");
+    c.set_list("history", &[1.5, 2.0]);
+    c.set_list::<u32>("empty", &[]);
+    c.set_edges(&p.g, "tree", &[edges[3], edges[0]]);
+    c.set_rng("rng", &rng);
+
+    let file = tmp("write-read");
+    c.write(&file).unwrap();
+    let read = Checkpoint::read(&file).unwrap();
+    assert_eq!(c, read);
+    assert_eq!(Ok(20), read.get::<u32>("iter"));
+    assert_eq!(Ok("a b".to_string()), read.get("name"));
+    assert_eq!(Ok(vec![1.5, 2.0]), read.get_list("history"));
+    assert_eq!(Ok(vec![]), read.get_list::<u32>("empty"));
+    assert_eq!(Ok(vec![edges[3], edges[0]]), read.get_edges(&p.g, "tree"));
+    let mut saved = read.get_rng("rng").unwrap();
+    assert_eq!(rng.next_u64(), saved.next_u64());
+
+    assert_eq!(Err("missing x".into()), read.get::<u32>("x"));
+    assert_eq!(
+        Err("name: invalid value a b".into()),
+        read.get::<u32>("name")
+    );
+    c.set_list("tree", &[20]);
+    assert_eq!(
+        Err("tree: invalid edge 20".into()),
+        c.get_edges(&p.g, "tree")
+    );
+}
+
+#[test]
+fn resume() {
+    let mut rng = Seed::from(4).new_rng(RngVersion::V1, Stream::Generate);
+    let p = new_random_instance("z30-90-100", 30, 90, 100, (1, 100), &mut rng);
+    let file = tmp("resume");
+    let perturbations = PerturbationKind::ALL;
+    let acceptances = [
+        Acceptance::RandomWalk,
+        Acceptance::LateAcceptance(5),
+        Acceptance::Annealing {
+            temperature: 100.0,
+            cooling: 0.9,
+        },
+    ];
+    let algs = [Algorithm::IlsTwoEx, Algorithm::IlsFourEx];
+    for (j, &alg) in algs.iter().enumerate() {
+        for (i, &ils_perturbation) in perturbations.iter().enumerate() {
+            let config = SolverConfig {
+                seed: Seed::from(1),
+                rng_version: [RngVersion::V1, RngVersion::V2][(i + j) % 2],
+                alg,
+                ils_max_iters: Some(60),
+                ils_excludes: 3,
+                ils_perturbation,
+                ils_acceptance: acceptances[i % acceptances.len()],
+                ils_restart: 15,
+                ils_restart_to_best: 7,
+                checkpoint_every: 10,
+                no_exact: true,
+                ..SolverConfig::default()
+            };
+            let full = solve(&p, &config);
+
+            // interrupted after the checkpoint of iteration 20
+            let _ = std::fs::remove_file(&file);
+            let interrupted = SolverConfig {
+                ils_max_iters: Some(27),
+                checkpoint: Some(file.clone()),
+                ..config.clone()
+            };
+            solve(&p, &interrupted);
+
+            let c = Checkpoint::read(&file).unwrap();
+            assert_eq!(Ok(20), c.get::<u32>("iter"));
+            let resumed = SolverConfig {
+                checkpoint: Some(file.clone()),
+                resume: Some(c),
+                ..config.clone()
+            };
+            let resumed = solve(&p, &resumed);
+            assert_eq!(full.tree, resumed.tree, "{} {}", alg, ils_perturbation);
+            assert_eq!(full.weight, resumed.weight);
+            assert_eq!(full.num_conflicts, resumed.num_conflicts);
+        }
+    }
+}
+
+#[test]
+fn invalid_configs() {
+    let mut rng = Seed::from(4).new_rng(RngVersion::V1, Stream::Generate);
+    let p = new_random_instance("z10-20-10", 10, 20, 10, (1, 100), &mut rng);
+    let config = SolverConfig {
+        seed: Seed::from(1),
+        checkpoint: Some(tmp("invalid")),
+        no_exact: true,
+        ..SolverConfig::default()
+    };
+    assert_eq!(Ok(()), config.check_checkpoint(&p));
+
+    let check = |config: SolverConfig| config.check_checkpoint(&p).unwrap_err();
+    let error = "only ils-2ex and ils-4ex without two phases can use checkpoints";
+    assert_eq!(
+        error,
+        check(SolverConfig {
+            alg: Algorithm::IlsTwoExFourEx,
+            ..config.clone()
+        })
+    );
+    assert_eq!(
+        error,
+        check(SolverConfig {
+            two_phase: true,
+            ..config.clone()
+        })
+    );
+    assert_eq!(
+        "the rng version 0 can not be saved in a checkpoint",
+        check(SolverConfig {
+            rng_version: RngVersion::V0,
+            ..config.clone()
+        })
+    );
+
+    let mut c = Checkpoint::new();
+    c.set("instance", "z10-20-10");
+    assert_eq!(
+        "missing vertices",
+        check(SolverConfig {
+            resume: Some(c),
+            ..config.clone()
+        })
+    );
+
+    let config = SolverConfig {
+        ils_max_iters: Some(30),
+        checkpoint_every: 10,
+        ..config
+    };
+    solve(&p, &config);
+    let c = Checkpoint::read(&tmp("invalid")).unwrap();
+    assert_eq!(
+        Ok(()),
+        SolverConfig {
+            resume: Some(c.clone()),
+            ..config.clone()
+        }
+        .check_checkpoint(&p)
+    );
+    assert_eq!(
+        "the checkpoint was saved with seed 1 (not 2)",
+        check(SolverConfig {
+            seed: Seed::from(2),
+            resume: Some(c.clone()),
+            ..config.clone()
+        })
+    );
+    assert_eq!(
+        "the checkpoint was saved with ils_restart 1000000000 (not 5)",
+        check(SolverConfig {
+            ils_restart: 5,
+            resume: Some(c.clone()),
+            ..config.clone()
+        })
+    );
+    let initial = p.mst();
+    assert!(check(SolverConfig {
+        initial: Some(initial),
+        resume: Some(c.clone()),
+        ..config.clone()
+    })
+    .starts_with("the checkpoint was saved with initial none (not "));
+    let q = p.map_weights(u64::from);
+    let resume = SolverConfig {
+        resume: Some(c),
+        ..config
+    };
+    assert_eq!(
+        Err("the checkpoint was saved with weights u32 (not u64)".into()),
+        resume.check_checkpoint(&q)
+    );
+}
@@ -0,0 +1,33 @@
+// Fixtures shared by the tests, each test uses only some of them
+#![allow(dead_code)]
+
+use fera::fun::vec;
+use fera::graph::algs::Kruskal;
+use fera::graph::prelude::*;
+use mstcc::*;
+
+// A random instance zn-m-c with weights in [1, 100] generated with the version 1 stream of seed
+pub fn instance(seed: Seed, n: usize, m: usize, c: usize) -> MstCcProblem {
+    let mut rng = seed.new_rng(RngVersion::V1, Stream::Generate);
+    let name = format!("z{}-{}-{}", n, m, c);
+    new_random_instance(&name, n, m, c, (1, 100), &mut rng)
+}
+
+// A Kruskal on a random permutation of the edges
+pub fn random_tree<W>(p: &MstCcProblem<W>, rng: &mut StreamRng) -> Vec<Edge<StaticGraph>> {
+    let mut edges = vec(p.g.edges());
+    rng.shuffle(&mut edges);
+    vec(p.g.kruskal().edges(&edges))
+}
+
+// A restart of the ils that adds to the tree a Kruskal on the edges shuffled again in each call
+pub fn random_restart<'a, W>(
+    p: &'a MstCcProblem<W>,
+    mut rng: StreamRng,
+) -> impl FnMut(&mut Vec<Edge<StaticGraph>>) + 'a {
+    let mut edges = vec(p.g.edges());
+    move |tree| {
+        rng.shuffle(&mut edges);
+        tree.extend(p.g.kruskal().edges(&edges));
+    }
+}
@@ -0,0 +1,91 @@
+extern crate mstcc;
+
+use mstcc::*;
+
+#[test]
+fn experiment() {
+    let experiment = parse_experiment(
+        r#"
+# comment
+repetitions = 30
+seed = 42
+
+[instances.small]
+files = ["a.gcc", "b # not a comment.gcc"]
+
+[instances.big]
+files = [
+    "c.gcc", # comment
+    "d.gcc",
+]
+
+[config.B1]
+init = "random"
+alg = "ils-2ex"
+ils_max_iters = "m"
+ils-excludes = 3
+alpha = 1.5
+stop-on-feasible = true
+instances = ["small"]
+
+[config.B2]
+init = "greedy"
+alg = "ils-4ex"
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(Some(&Json::Int(30)), experiment.setting("repetitions"));
+    assert_eq!(Some(&Json::Int(42)), experiment.setting("seed"));
+    assert_eq!(None, experiment.setting("threads"));
+
+    assert_eq!(
+        Some(&["a.gcc".to_string(), "b # not a comment.gcc".to_string()][..]),
+        experiment.instance_set("small")
+    );
+    assert_eq!(
+        Some(&["c.gcc".to_string(), "d.gcc".to_string()][..]),
+        experiment.instance_set("big")
+    );
+
+    assert_eq!(
+        vec![
+            AlgorithmConfig {
+                name: "B1".into(),
+                params: vec![
+                    ("init".into(), Json::Str("random".into())),
+                    ("alg".into(), Json::Str("ils-2ex".into())),
+                    ("ils-max-iters".into(), Json::Str("m".into())),
+                    ("ils-excludes".into(), Json::Int(3)),
+                    ("alpha".into(), Json::Float(1.5)),
+                    ("stop-on-feasible".into(), Json::Bool(true)),
+                ],
+                instances: vec!["small".into()],
+            },
+            AlgorithmConfig {
+                name: "B2".into(),
+                params: vec![
+                    ("init".into(), Json::Str("greedy".into())),
+                    ("alg".into(), Json::Str("ils-4ex".into())),
+                ],
+                instances: vec![],
+            },
+        ],
+        experiment.configs
+    );
+}
+
+#[test]
+fn experiment_errors() {
+    let error = |s: &str| parse_experiment(s).unwrap_err();
+    assert_eq!("line 2: duplicated key a", error("a = 1\na = 2"));
+    assert_eq!("line 1: invalid value x", error("a = x"));
+    assert_eq!("line 1: unterminated array", error("a = [1,\n2"));
+    assert_eq!("line 1: section must be [kind.name]", error("[config]"));
+    assert_eq!("invalid section [other.a]", error("[other.a]"));
+    assert_eq!("invalid key repetition", error("repetition = 30"));
+    assert_eq!(
+        "config.B1: undefined instance set small",
+        error("[config.B1]\ninstances = [\"small\"]")
+    );
+}
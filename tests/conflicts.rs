@@ -76,6 +76,23 @@ fn remove_edge() {
     conflicts.check();
 }
 
+#[test]
+fn pairs() {
+    let (p, e) = new();
+    let mut conflicts = TrackConflicts::new(&p);
+    conflicts.add_edge(e[5]);
+    conflicts.add_edge(e[1]);
+    conflicts.add_edge(e[3]);
+    conflicts.add_edge(e[0]);
+    assert_eq!(
+        vec![(e[0], e[1]), (e[0], e[3]), (e[1], e[5])],
+        conflicts.pairs()
+    );
+
+    conflicts.remove_edge(e[0]);
+    assert_eq!(vec![(e[1], e[5])], conflicts.pairs());
+}
+
 fn new() -> (MstCcProblem, Vec<Edge<StaticGraph>>) {
     let mut b = <StaticGraph as WithBuilder>::Builder::new(4, 6);
     b.add_edge(0, 1); // e0
@@ -89,9 +106,9 @@ fn new() -> (MstCcProblem, Vec<Edge<StaticGraph>>) {
     let w = g.edge_prop(1);
     let mut p = MstCcProblem {
         name: "test".to_owned(),
-        g: g,
-        w: w,
-        cc: cc,
+        g,
+        w,
+        cc,
         num_cc: 4,
         alpha: 1.into(),
         beta: 0.into(),
@@ -36,3 +36,34 @@ fn test1() {
         }
     }
 }
+
+#[test]
+fn long_path() {
+    // the recursive dfs used before overflowed the stack of the test thread
+    let n = 1_000_000;
+    let mut b = StaticGraph::builder(n, n - 1);
+    for i in 0..n - 1 {
+        b.add_edge(i, i + 1);
+    }
+    let g = b.finalize();
+    let edges = g.edges().collect::<Vec<_>>();
+    let (first, _) = g.ends(edges[0]);
+    let (_, last) = g.ends(edges[n - 2]);
+
+    let mut track_con = TrackConnectivity1::new(&g);
+    track_con.set_edges(g.edges());
+    let (u, v) = g.ends(edges[n / 2]);
+    track_con.disconnect(u, v);
+    assert!(track_con.is_connected(first, u));
+    assert!(track_con.is_connected(v, last));
+    assert!(!track_con.is_connected(u, v));
+    assert!(!track_con.is_connected(first, last));
+
+    // the dfs is also run after a replacement
+    track_con.replace_edge(edges[n / 2], edges[n / 2]);
+    let (u, v) = g.ends(edges[10]);
+    track_con.disconnect(u, v);
+    assert!(track_con.is_connected(first, u));
+    assert!(!track_con.is_connected(first, v));
+    assert!(track_con.is_connected(v, last));
+}
@@ -87,3 +87,31 @@ fn test2() {
         }
     }
 }
+
+#[test]
+fn long_path() {
+    // the recursive dfs used before overflowed the stack of the test thread
+    let n = 1_000_000;
+    let mut b = StaticGraph::builder(n, n - 1);
+    for i in 0..n - 1 {
+        b.add_edge(i, i + 1);
+    }
+    let g = b.finalize();
+    let edges = g.edges().collect::<Vec<_>>();
+    let (first, _) = g.ends(edges[0]);
+    let (_, last) = g.ends(edges[n - 2]);
+
+    let mut track_con = TrackConnectivity2::new(&g);
+    track_con.set_edges(g.edges());
+    let e1 = g.ends(edges[n / 3]);
+    let e2 = g.ends(edges[2 * n / 3]);
+    track_con.disconnect2(e1, e2);
+    assert!(track_con.is_connected(first, e1.0));
+    assert!(track_con.is_connected(e1.1, e2.0));
+    assert!(track_con.is_connected(e2.1, last));
+    assert!(!track_con.is_connected(first, e1.1));
+    assert!(!track_con.is_connected(e1.1, e2.1));
+    assert!(!track_con.is_connected(first, last));
+    assert!(track_con.check_reconnect((first, e1.1), (e1.1, last)));
+    assert!(!track_con.check_reconnect((first, e1.1), (first, e2.0)));
+}
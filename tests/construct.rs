@@ -0,0 +1,46 @@
+extern crate fera;
+extern crate mstcc;
+
+use fera::graph::algs::Trees;
+use fera::graph::prelude::*;
+use mstcc::*;
+
+#[test]
+fn prim_greedy() {
+    let seed = Seed::from(11);
+    let mut rng = seed.new_rng(RngVersion::V1, Stream::Generate);
+    let p = new_random_instance("z40-150-100", 40, 150, 100, (1, 100), &mut rng);
+    for &beta in &[0, 10, 10_000] {
+        p.beta.set(beta);
+        let mut tree = vec![];
+        new_prim_greedy(&p, &mut tree);
+        assert!(p.g.spanning_subgraph(&tree).is_tree());
+        assert_eq!(prim_greedy_quadratic(&p), tree);
+    }
+}
+
+// In each step adds the edge leaving the tree with the smallest (obj, index)
+fn prim_greedy_quadratic(p: &MstCcProblem) -> Vec<Edge<StaticGraph>> {
+    let g = &p.g;
+    let index = g.edge_index();
+    let mut in_tree = g.default_vertex_prop(false);
+    in_tree[g.vertices().next().unwrap()] = true;
+    let mut conflicts = TrackConflicts::new(p);
+    let mut tree = vec![];
+    while tree.len() + 1 < g.num_vertices() {
+        let e = g
+            .edges()
+            .filter(|&e| {
+                let (u, v) = g.ends(e);
+                in_tree[u] != in_tree[v]
+            })
+            .min_by_key(|&e| (p.obj(p.w.get(e), conflicts[e]), index.get(e)))
+            .unwrap();
+        let (u, v) = g.ends(e);
+        in_tree[u] = true;
+        in_tree[v] = true;
+        conflicts.add_edge(e);
+        tree.push(e);
+    }
+    tree
+}
@@ -0,0 +1,70 @@
+extern crate fera;
+extern crate mstcc;
+
+use std::env;
+use std::fs::File;
+
+use fera::graph::algs::Components;
+use fera::graph::prelude::*;
+use mstcc::{
+    check_random_instance, new_random_instance, read_sammer_urrutia, write_sammer_urrutia,
+    RngVersion, Seed, Stream,
+};
+
+#[test]
+fn random_instance() {
+    let mut rng = Seed::from(1).new_rng(RngVersion::LATEST, Stream::Generate);
+    for &(n, m, c) in &[
+        (1, 0, 0),
+        (2, 1, 0),
+        (10, 9, 20),
+        (10, 45, 100),
+        (50, 200, 500),
+    ] {
+        let p = new_random_instance("test", n, m, c, (5, 10), &mut rng);
+        assert_eq!(n, p.g.num_vertices());
+        assert_eq!(m, p.g.num_edges());
+        assert_eq!(c, p.num_cc);
+        assert!(p.g.is_connected());
+        assert_eq!(2 * c, p.g.edges().map(|e| p.cc[e].len()).sum::<usize>());
+        for (e, u, v) in p.g.edges_with_ends() {
+            assert!(u != v);
+            assert!(5 <= p.w[e] && p.w[e] <= 10);
+            assert!(!p.cc[e].contains(&e));
+        }
+    }
+}
+
+#[test]
+fn random_instance_invalid_args() {
+    assert!(check_random_instance(5, 4, 6, (1, 1)).is_ok());
+    assert!(check_random_instance(0, 0, 0, (1, 100)).is_err());
+    assert!(check_random_instance(5, 3, 0, (1, 100)).is_err());
+    assert!(check_random_instance(5, 11, 0, (1, 100)).is_err());
+    assert!(check_random_instance(5, 4, 7, (1, 100)).is_err());
+    assert!(check_random_instance(5, 4, 0, (2, 1)).is_err());
+}
+
+#[test]
+fn write_read() {
+    let mut rng = Seed::from(2).new_rng(RngVersion::LATEST, Stream::Generate);
+    let p = new_random_instance("z20-50-100", 20, 50, 100, (1, 100), &mut rng);
+    let path = env::temp_dir().join("mstcc-generate-write-read.gcc");
+    write_sammer_urrutia(&p, &mut File::create(&path).unwrap()).unwrap();
+
+    let q = read_sammer_urrutia(path.to_str().unwrap()).unwrap();
+    assert_eq!(p.name, q.name);
+    assert_eq!(p.num_cc, q.num_cc);
+    assert_eq!(
+        p.g.edges_with_ends().collect::<Vec<_>>(),
+        q.g.edges_with_ends().collect::<Vec<_>>()
+    );
+    for e in p.g.edges() {
+        assert_eq!(p.w[e], q.w[e]);
+        let mut a = p.cc[e].clone();
+        let mut b = q.cc[e].clone();
+        a.sort();
+        b.sort();
+        assert_eq!(a, b);
+    }
+}
@@ -0,0 +1,43 @@
+extern crate fera;
+extern crate mstcc;
+
+use fera::graph::algs::{Kruskal, Trees};
+use fera::graph::prelude::*;
+use fera::graph::sum_prop;
+use mstcc::*;
+
+#[test]
+fn grasp_construction() {
+    let seed = Seed::from(5);
+    let mut rng = seed.new_rng(RngVersion::V1, Stream::Generate);
+    let p = new_random_instance("z30-80-40", 30, 80, 40, (1, 100), &mut rng);
+    let mut rng = seed.new_rng(RngVersion::V1, Stream::Init);
+    for &rcl in &[
+        Rcl::Cardinality(0.0),
+        Rcl::Cardinality(0.3),
+        Rcl::Value(0.2),
+        Rcl::Value(1.0),
+    ] {
+        let mut tree = vec![];
+        new_grasp(&p, &mut tree, rcl, &mut rng);
+        assert!(p.g.spanning_subgraph(&tree).is_tree(), "{}", rcl);
+    }
+
+    // without conflicts and alpha 0 the construction is the minimum spanning tree
+    p.beta.set(0);
+    let mst: u32 = sum_prop(&p.w, p.g.kruskal().weight(&p.w));
+    for &rcl in &[Rcl::Cardinality(0.0), Rcl::Value(0.0)] {
+        let mut tree = vec![];
+        new_grasp(&p, &mut tree, rcl, &mut rng);
+        assert_eq!(mst, sum_prop(&p.w, &tree), "{}", rcl);
+    }
+}
+
+#[test]
+fn rcl_parse() {
+    assert_eq!(Ok(Rcl::Value(0.1)), "value:0.1".parse());
+    assert_eq!(Ok(Rcl::Cardinality(1.0)), "cardinality:1".parse());
+    for s in &["value", "value:2", "cardinality:x", "random:0.1"] {
+        assert!(s.parse::<Rcl>().is_err(), "{}", s);
+    }
+}
@@ -0,0 +1,71 @@
+extern crate fera;
+extern crate mstcc;
+
+mod common;
+
+use fera::graph::algs::Trees;
+use fera::graph::prelude::*;
+use fera::graph::sum_prop;
+use mstcc::*;
+
+#[test]
+fn acceptance_parse() {
+    for s in &[
+        "better",
+        "walk",
+        "threshold:2.5",
+        "late:100",
+        "annealing:50:0.99",
+    ] {
+        assert_eq!(*s, s.parse::<Acceptance>().unwrap().to_string());
+    }
+    assert_eq!(
+        Ok(Acceptance::Annealing {
+            temperature: 50.0,
+            cooling: 0.99,
+        }),
+        "annealing:50:0.99".parse()
+    );
+    for s in &["", "best", "walk:1", "threshold", "late:x", "annealing:1"] {
+        assert!(s.parse::<Acceptance>().is_err(), "{}", s);
+    }
+}
+
+#[test]
+fn acceptance_never_worse_than_initial() {
+    let seed = Seed::from(3);
+    let p = common::instance(seed, 40, 120, 80);
+    let acceptances = [
+        Acceptance::Better,
+        Acceptance::RandomWalk,
+        Acceptance::Threshold(5.0),
+        Acceptance::LateAcceptance(10),
+        Acceptance::Annealing {
+            temperature: 100.0,
+            cooling: 0.95,
+        },
+    ];
+    for &acceptance in &acceptances {
+        let mut rng = seed.new_rng(RngVersion::V1, Stream::Init);
+        let mut tree = common::random_tree(&p, &mut rng);
+        let initial = p.obj(
+            sum_prop(&p.w, &tree),
+            TrackConflicts::with_edges(&p, &tree).total(),
+        );
+        let restart = |_: &mut Vec<_>| unreachable!();
+        let mut ils = Ils::new(&p, restart, RandomExclusion::new(&p));
+        ils.max_iters = 100;
+        ils.num_excludes = 3;
+        ils.acceptance = acceptance;
+        let mut one = OneEdgeReplacement::new(&p);
+        let mut rng = seed.new_rng(RngVersion::V1, Stream::Perturbation);
+        let conflicts = ils.run(&mut tree, &mut rng, |tree| one.run(tree));
+        assert!(p.g.spanning_subgraph(&tree).is_tree(), "{}", acceptance);
+        assert_eq!(TrackConflicts::with_edges(&p, &tree).total(), conflicts);
+        assert!(
+            p.obj(sum_prop(&p.w, &tree), conflicts) <= initial,
+            "{}",
+            acceptance
+        );
+    }
+}
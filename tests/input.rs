@@ -0,0 +1,118 @@
+extern crate fera;
+extern crate mstcc;
+
+use std::env;
+use std::fs::File;
+use std::io::Write;
+
+use fera::fun::vec;
+use fera::graph::prelude::*;
+use mstcc::{read_sammer_urrutia, read_solution, MstCcProblem};
+
+const INSTANCE: &str = "# test instance
+test
+4
+6
+2
+0 1 3
+0 2 1
+0 3 4
+1 2 2
+1 3 5
+2 3 1
+0 2 2 3
+0 1 1 2
+";
+
+#[test]
+fn read_solution_valid() {
+    let p: MstCcProblem = read_sammer_urrutia(&write_tmp("valid.gcc", INSTANCE)).unwrap();
+
+    let tree = read_solution(&p, &write_tmp("valid.sol", "1-2 2-3 0-3")).unwrap();
+    assert_eq!(vec![(1, 2), (2, 3), (0, 3)], vec(p.g.ends(&tree)));
+
+    // a text output line of the program
+    let line = "test,0.00,0,7,1-2 3-2 0-3 \n";
+    let tree = read_solution(&p, &write_tmp("line.sol", line)).unwrap();
+    assert_eq!(vec![(1, 2), (3, 2), (0, 3)], vec(p.g.ends(&tree)));
+
+    // a csv output of the program
+    let csv = "name,seed,time,conflicts,weight,obj,solution\ntest,1,0.00,0,7,7,1-2 3-2 0-3\n";
+    let tree = read_solution(&p, &write_tmp("csv.sol", csv)).unwrap();
+    assert_eq!(vec![(1, 2), (3, 2), (0, 3)], vec(p.g.ends(&tree)));
+}
+
+#[test]
+fn read_solution_invalid() {
+    let p: MstCcProblem = read_sammer_urrutia(&write_tmp("invalid.gcc", INSTANCE)).unwrap();
+    let read = |name, s| read_solution(&p, &write_tmp(name, s));
+
+    assert!(read("not-edge.sol", "1-2 2-3 0-4").is_err());
+    assert!(read("not-tree.sol", "0-1 1-2 0-2").is_err());
+    assert!(read("duplicated.sol", "0-1 1-2 0-1").is_err());
+    assert!(read("not-spanning.sol", "0-1 1-2").is_err());
+    assert!(read("parse.sol", "0-1 1-2 2+3").is_err());
+}
+
+#[test]
+fn read_large_weights() {
+    let large = INSTANCE
+        .replace("0 1 3\n", "0 1 3000000000\n")
+        .replace("1 3 5\n", "1 3 5000000000\n");
+    let file = write_tmp("large.gcc", &large);
+    assert!(read_sammer_urrutia::<u32>(&file).is_err());
+
+    let p: MstCcProblem<u64> = read_sammer_urrutia(&file).unwrap();
+    let tree = read_solution(&p, &write_tmp("large.sol", "0-1 1-3 0-2")).unwrap();
+    assert_eq!(8_000_000_001, p.weight(&tree));
+    p.alpha.set(3);
+    p.beta.set(10_000);
+    assert_eq!(
+        24_000_000_003 + 10_000 * 500_000,
+        p.obj(p.weight(&tree), 500_000)
+    );
+
+    let p: MstCcProblem<f64> = read_sammer_urrutia(&file).unwrap();
+    assert_eq!(8_000_000_001.0, p.weight(&tree));
+}
+
+#[test]
+fn read_decimal_weights() {
+    let decimal = INSTANCE
+        .replace("0 1 3\n", "0 1 2.5\n")
+        .replace("2 3 1\n", "2 3 1e-1\n");
+    let file = write_tmp("decimal.gcc", &decimal);
+    assert!(read_sammer_urrutia::<u64>(&file).is_err());
+    let p: MstCcProblem<f64> = read_sammer_urrutia(&file).unwrap();
+    let tree = read_solution(&p, &write_tmp("decimal.sol", "0-1 1-2 2-3")).unwrap();
+    assert_eq!(2.5 + 2.0 + 0.1, p.weight(&tree));
+
+    for weight in &["nan", "inf", "-inf"] {
+        let invalid = INSTANCE.replace("0 1 3\n", &format!("0 1 {}\n", weight));
+        let file = write_tmp("invalid-weight.gcc", &invalid);
+        assert!(read_sammer_urrutia::<f64>(&file).is_err());
+    }
+}
+
+#[test]
+fn read_invalid_instance() {
+    let read = |name, s: &str| read_sammer_urrutia::<u64>(&write_tmp(name, s));
+
+    assert!(read("truncated.gcc", &INSTANCE[..INSTANCE.len() - 8]).is_err());
+    assert!(read("no-weight.gcc", &INSTANCE.replace("0 1 3\n", "0 1\n")).is_err());
+    assert!(read("vertex.gcc", &INSTANCE.replace("0 1 3\n", "0 4 3\n")).is_err());
+    // without the edge 0 3 the conflict 0 1 0 3 names a non edge
+    let non_edge = INSTANCE
+        .replace("0 3 4\n", "1 2 4\n")
+        .replace("0 1 1 2\n", "0 1 0 3\n");
+    assert!(read("non-edge.gcc", &non_edge).is_err());
+}
+
+fn write_tmp(name: &str, contents: &str) -> String {
+    let path = env::temp_dir().join(format!("mstcc-input-{}", name));
+    File::create(&path)
+        .unwrap()
+        .write_all(contents.as_bytes())
+        .unwrap();
+    path.to_str().unwrap().into()
+}
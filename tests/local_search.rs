@@ -0,0 +1,55 @@
+extern crate fera;
+extern crate mstcc;
+
+mod common;
+
+use fera::graph::algs::Trees;
+use fera::graph::prelude::*;
+use fera::graph::sum_prop;
+use mstcc::*;
+
+#[test]
+fn only_conflicting_and_feasible_only() {
+    let seed = Seed::from(23);
+    let p = common::instance(seed, 30, 90, 60);
+    let mut rng = seed.new_rng(RngVersion::V1, Stream::Init);
+    let mut one = OneEdgeReplacement::new(&p);
+    let mut two = TwoEdgeReplacement::new(&p);
+    for _ in 0..10 {
+        let initial = common::random_tree(&p, &mut rng);
+        let conflicts = |tree: &[_]| TrackConflicts::with_edges(&p, tree).total();
+
+        // phase one: only the conflicts matter
+        p.alpha.set(0);
+        p.beta.set(1);
+        one.only_conflicting = true;
+        two.only_conflicting = true;
+        let mut tree1 = initial.clone();
+        let mut tree2 = initial.clone();
+        let c1 = one.run(&mut tree1);
+        let c2 = two.run(&mut tree2);
+        assert_eq!(conflicts(&tree1), c1);
+        assert_eq!(conflicts(&tree2), c2);
+        assert!(conflicts(&tree1) <= conflicts(&initial));
+        assert!(conflicts(&tree2) <= conflicts(&initial));
+        assert!(p.g.spanning_subgraph(&tree1).is_tree());
+        assert!(p.g.spanning_subgraph(&tree2).is_tree());
+
+        // phase two: the conflicts never increase
+        p.alpha.set(1);
+        p.beta.set(0);
+        one.only_conflicting = false;
+        two.only_conflicting = false;
+        one.feasible_only = true;
+        two.feasible_only = true;
+        let weight: u32 = sum_prop(&p.w, &tree1);
+        let c = conflicts(&tree1);
+        assert!(one.run(&mut tree1) <= c);
+        let new_weight: u32 = sum_prop(&p.w, &tree1);
+        assert!(new_weight <= weight);
+        let c = conflicts(&tree2);
+        assert!(two.run(&mut tree2) <= c);
+        one.feasible_only = false;
+        two.feasible_only = false;
+    }
+}
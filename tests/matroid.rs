@@ -0,0 +1,86 @@
+extern crate fera;
+extern crate mstcc;
+
+use fera::fun::vec;
+use fera::graph::algs::Trees;
+use fera::graph::prelude::*;
+use fera::graph::sum_prop;
+use mstcc::*;
+
+#[test]
+fn matroid_intersection_optimal() {
+    let seed = Seed::from(17);
+    let mut rng = seed.new_rng(RngVersion::V1, Stream::Generate);
+    let mut num_infeasible = 0;
+    for i in 0..100 {
+        let n = 3 + i % 5;
+        let m = (n - 1 + i % 7).min(n * (n - 1) / 2).min(12);
+        let mut p = new_random_instance("small", n, m, 0, (1, 20), &mut rng);
+        // conflicts in cliques of size 1 to 3
+        let mut edges = vec(p.g.edges());
+        rng.shuffle(&mut edges);
+        while !edges.is_empty() {
+            let k = (1 + rng.gen_index(3)).min(edges.len());
+            let clique = edges.split_off(edges.len() - k);
+            for &e in &clique {
+                p.cc[e] = clique.iter().cloned().filter(|&f| f != e).collect();
+            }
+            p.num_cc += k * (k - 1) / 2;
+        }
+
+        let exact = MatroidIntersection::new(&p).unwrap();
+        match (exact.solve(), brute_force(&p)) {
+            (Ok(tree), Some(weight)) => {
+                assert!(p.g.spanning_subgraph(&tree).is_tree());
+                assert_eq!(0, TrackConflicts::with_edges(&p, &tree).total());
+                assert_eq!(weight, sum_prop(&p.w, &tree));
+            }
+            (Err(forest), None) => {
+                assert!(forest.len() + 1 < n);
+                assert_eq!(0, TrackConflicts::with_edges(&p, &forest).total());
+                num_infeasible += 1;
+            }
+            (result, weight) => panic!("{:?} {:?}", result.map(|t| t.len()), weight),
+        }
+    }
+    assert!(num_infeasible > 0 && num_infeasible < 100);
+}
+
+#[test]
+fn matroid_intersection_structure() {
+    let seed = Seed::from(17);
+    let mut rng = seed.new_rng(RngVersion::V1, Stream::Generate);
+    let mut p = new_random_instance("path", 5, 8, 0, (1, 20), &mut rng);
+    assert!(MatroidIntersection::new(&p).is_some());
+
+    // a path of three conflicting edges is not a clique
+    let edges = vec(p.g.edges());
+    p.cc[edges[0]] = vec![edges[1]];
+    p.cc[edges[1]] = vec![edges[0], edges[2]];
+    p.cc[edges[2]] = vec![edges[1]];
+    assert!(MatroidIntersection::new(&p).is_none());
+
+    p.cc[edges[0]].push(edges[2]);
+    p.cc[edges[2]].push(edges[0]);
+    assert!(MatroidIntersection::new(&p).is_some());
+}
+
+// The minimum weight of a conflict free spanning tree
+fn brute_force(p: &MstCcProblem) -> Option<u32> {
+    let edges = vec(p.g.edges());
+    let n = p.g.num_vertices();
+    (0u32..1 << edges.len())
+        .filter(|set| set.count_ones() as usize + 1 == n)
+        .map(|set| {
+            (0..edges.len())
+                .filter(|&i| set & (1 << i) != 0)
+                .map(|i| edges[i])
+                .collect::<Vec<_>>()
+        })
+        .filter(|tree| {
+            p.g.spanning_subgraph(tree).is_tree()
+                && TrackConflicts::with_edges(p, tree).total() == 0
+        })
+        .map(|tree| sum_prop(&p.w, &tree))
+        .min()
+}
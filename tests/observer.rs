@@ -0,0 +1,102 @@
+extern crate fera;
+extern crate mstcc;
+
+mod common;
+
+use std::cell::RefCell;
+
+use mstcc::*;
+
+#[derive(Default)]
+struct Events {
+    improvements: RefCell<Vec<(u32, Cost<u32>)>>,
+    iterations: RefCell<Vec<u32>>,
+    restarts: RefCell<Vec<(u32, bool)>>,
+    moves: RefCell<Vec<(Cost<u32>, Cost<u32>)>>,
+}
+
+impl Observer<u32> for Events {
+    fn on_improvement(&self, alg: &str, iter: u32, _old: Cost<u32>, new: Cost<u32>) {
+        assert_eq!("ils", alg);
+        self.improvements.borrow_mut().push((iter, new));
+    }
+
+    fn on_iteration(&self, _alg: &str, iter: u32, _cost: Cost<u32>) {
+        self.iterations.borrow_mut().push(iter);
+    }
+
+    fn on_restart(&self, _alg: &str, iter: u32, to_best: bool) {
+        self.restarts.borrow_mut().push((iter, to_best));
+    }
+
+    fn on_move(&self, alg: &str, old: Cost<u32>, new: Cost<u32>) {
+        assert_eq!("2ex", alg);
+        self.moves.borrow_mut().push((old, new));
+    }
+}
+
+#[test]
+fn ils_events() {
+    let seed = Seed::from(12);
+    let p = common::instance(seed, 40, 120, 80);
+    p.beta.set(1000);
+    let events = Events::default();
+
+    let restart = common::random_restart(&p, seed.new_rng(RngVersion::V1, Stream::Init));
+    let mut tree = vec![];
+    let mut ils = Ils::new(&p, restart, RandomExclusion::new(&p));
+    ils.max_iters = 50;
+    ils.num_excludes = 2;
+    ils.iters_restart = 7;
+    ils.iters_restart_to_best = 3;
+    ils.observer = &events;
+    (ils.restart)(&mut tree);
+    let mut one = OneEdgeReplacement::new(&p);
+    one.observer = &events;
+    let mut rng = seed.new_rng(RngVersion::V1, Stream::Perturbation);
+    let conflicts = ils.run(&mut tree, &mut rng, |tree| one.run(tree));
+
+    // restarts skip the end of the iteration, but not the local search
+    assert_eq!((0..50).collect::<Vec<_>>(), *events.iterations.borrow());
+
+    let improvements = events.improvements.borrow();
+    assert_eq!(0, improvements[0].0);
+    let obj = |c: Cost<u32>| p.obj(c.weight, c.num_conflicts);
+    for w in improvements.windows(2) {
+        assert!(w[0].0 < w[1].0);
+        assert!(obj(w[1].1) < obj(w[0].1));
+    }
+    let last = improvements.last().unwrap().1;
+    assert_eq!(conflicts, last.num_conflicts);
+    assert_eq!(p.weight(&tree), last.weight);
+
+    let restarts = events.restarts.borrow();
+    assert!(restarts.iter().any(|&(_, to_best)| to_best));
+    for &(iter, _) in &*restarts {
+        assert!(improvements.iter().all(|&(i, _)| i != iter));
+    }
+
+    let moves = events.moves.borrow();
+    assert!(!moves.is_empty());
+    for &(old, new) in &*moves {
+        assert!(obj(new) < obj(old));
+    }
+}
+
+#[test]
+fn solve_observed_events() {
+    let seed = Seed::from(4);
+    let p = common::instance(seed, 30, 90, 60);
+    let events = Events::default();
+    let config = SolverConfig {
+        seed,
+        ils_max_iters: Some(20),
+        ..SolverConfig::default()
+    };
+    let solution = solve_observed(&p, &config, &events);
+    assert_eq!(20, events.iterations.borrow().len());
+    let last = events.improvements.borrow().last().unwrap().1;
+    assert_eq!(solution.num_conflicts, last.num_conflicts);
+    assert_eq!(solution.weight, last.weight);
+    assert_eq!(solution.tree, solve(&p, &config).tree);
+}
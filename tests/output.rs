@@ -0,0 +1,54 @@
+extern crate fera;
+extern crate mstcc;
+
+use std::env;
+use std::fs;
+
+use fera::graph::prelude::*;
+use mstcc::*;
+
+#[test]
+fn csv_fields() {
+    assert_eq!("z10-20-5", csv_field("z10-20-5"));
+    assert_eq!("\"a,b\"", csv_field("a,b"));
+    assert_eq!("\"say \"\"hi\"\"\"", csv_field("say \"hi\""));
+    assert_eq!(
+        vec!["a,b", "1", "say \"hi\"", ""],
+        split_csv_line("\"a,b\",1,\"say \"\"hi\"\"\",")
+    );
+}
+
+#[test]
+fn csv_quoted_name() {
+    let mut b = MstCcProblemBuilder::<u32>::new("dir/a,b");
+    let u = b.add_vertex();
+    let v = b.add_vertex();
+    b.add_edge(u, v, 3);
+    let p = b.build().unwrap();
+    let tree = p.g.edges().collect::<Vec<_>>();
+    let report = Report {
+        p: &p,
+        seed: Seed::from(1),
+        params: vec![("init_from", "x,y.sol".into())],
+        elapsed: 0.5,
+        num_conflicts: 0,
+        weight: 3,
+        obj: 3,
+        tree: &tree,
+    };
+    let mut out = vec![];
+    report.write(OutputFormat::Csv, &mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(
+        "name,seed,init_from,time,conflicts,weight,obj,solution\n\
+         \"dir/a,b\",1,\"x,y.sol\",0.50,0,3,3,0-1\n",
+        out
+    );
+
+    let file = env::temp_dir().join("mstcc-output-quoted.csv");
+    fs::write(&file, out).unwrap();
+    let results = read_results(file.to_str().unwrap()).unwrap();
+    assert_eq!(1, results.len());
+    assert_eq!("dir/a,b", results[0].name);
+    assert_eq!(3.0, results[0].weight);
+}
@@ -0,0 +1,167 @@
+extern crate fera;
+extern crate mstcc;
+
+use std::collections::{HashMap, HashSet};
+
+use fera::graph::algs::{Kruskal, Trees};
+use fera::graph::prelude::*;
+use mstcc::*;
+
+fn check<P: Perturbation>(p: &MstCcProblem, mut perturbation: P) {
+    let mut rng = Seed::from(4).new_rng(RngVersion::LATEST, Stream::Perturbation);
+    let mut tree = p.g.kruskal().weight(&p.w).into_iter().collect::<Vec<_>>();
+    perturbation.start();
+    for strength in 0..10 {
+        let old = tree.clone();
+        perturbation.perturb(&mut tree, strength, &mut rng);
+        assert_eq!(p.g.num_vertices() - 1, tree.len());
+        assert!(p.g.spanning_subgraph(&tree).is_tree());
+        if strength == 0 {
+            assert_eq!(old, tree);
+        }
+    }
+}
+
+#[test]
+fn perturbations_keep_spanning_trees() {
+    let mut rng = Seed::from(5).new_rng(RngVersion::LATEST, Stream::Generate);
+    for &(n, m, c) in &[(2, 1, 0), (10, 9, 10), (10, 20, 30), (30, 100, 200)] {
+        let p = new_random_instance("test", n, m, c, (1, 10), &mut rng);
+        check(&p, RandomExclusion::new(&p));
+        check(&p, ConflictExclusion::new(&p));
+        check(&p, SubtreeExclusion::new(&p));
+        check(&p, RandomWalk::new(&p));
+        check(&p, ConflictDoubleBridge::new(&p));
+    }
+}
+
+#[test]
+fn conflict_exclusion_removes_conflicting_edges() {
+    let mut rng = Seed::from(6).new_rng(RngVersion::LATEST, Stream::Generate);
+    for _ in 0..10 {
+        let p = new_random_instance("test", 30, 100, 300, (1, 10), &mut rng);
+        let mut tree = p.g.kruskal().weight(&p.w).into_iter().collect::<Vec<_>>();
+        let conflicting = TrackConflicts::with_edges(&p, &tree)
+            .pairs()
+            .into_iter()
+            .flat_map(|(e, f)| vec![e, f])
+            .collect::<Vec<_>>();
+        let old = tree.clone();
+        ConflictExclusion::new(&p).perturb(&mut tree, 1, &mut rng);
+        let removed = old
+            .into_iter()
+            .filter(|e| !tree.contains(e))
+            .collect::<Vec<_>>();
+        assert_eq!(1, removed.len());
+        assert!(conflicting.is_empty() || conflicting.contains(&removed[0]));
+    }
+}
+
+#[test]
+fn random_reconnect_distribution() {
+    // complete graph with 5 vertices, the forest has the components {0, 1}, {2} and {3, 4}
+    let g = StaticGraph::new_complete(5);
+    let edge = |u, v| g.edge_by_ends(u, v);
+    let forest = vec![edge(0, 1), edge(3, 4)];
+    let exclude = vec![edge(1, 2), edge(2, 3)];
+    let n = 20_000;
+
+    // reference: Kruskal on a random permutation of the edges that are not excluded
+    let mut rng = Seed::from(1).new_rng(RngVersion::LATEST, Stream::Perturbation);
+    let mut edges = g
+        .edges()
+        .filter(|e| !exclude.contains(e))
+        .collect::<Vec<_>>();
+    let mut expected = HashMap::new();
+    for _ in 0..n {
+        rng.shuffle(&mut edges);
+        let tree = g.kruskal().edges(forest.iter().chain(&edges)).into_iter();
+        *expected.entry(key(&g, tree)).or_insert(0) += 1;
+    }
+
+    let mut reconnect = RandomReconnect::new(&g);
+    let mut rng = Seed::from(2).new_rng(RngVersion::LATEST, Stream::Perturbation);
+    let mut actual = HashMap::new();
+    for _ in 0..n {
+        let mut tree = forest.clone();
+        reconnect.reconnect(&mut tree, &exclude, &mut rng);
+        assert!(g.spanning_subgraph(&tree).is_tree());
+        assert!(tree.iter().all(|e| !exclude.contains(e)));
+        *actual.entry(key(&g, tree)).or_insert(0) += 1;
+    }
+
+    // 1 * 4 + 1 * 1 + 4 * 1 ways to join the components
+    assert_eq!(9, expected.len());
+    assert_same_distribution(&expected, &actual);
+}
+
+#[test]
+fn conflict_double_bridge_v2_distribution() {
+    let mut rng = Seed::from(7).new_rng(RngVersion::V1, Stream::Generate);
+    let p = new_random_instance("test", 6, 10, 8, (1, 10), &mut rng);
+    let tree = p.g.kruskal().weight(&p.w).into_iter().collect::<Vec<_>>();
+    let n = 20_000;
+
+    // the version 2 samples the edges, the version 1 shuffles and sorts all of them
+    let sample = |version| {
+        let mut perturbation = ConflictDoubleBridge::new(&p);
+        let mut rng = Seed::from(8).new_rng(version, Stream::Perturbation);
+        let mut trees = HashMap::new();
+        for _ in 0..n {
+            let mut t = tree.clone();
+            perturbation.perturb(&mut t, 2, &mut rng);
+            assert!(p.g.spanning_subgraph(&t).is_tree());
+            *trees.entry(key(&p.g, t)).or_insert(0) += 1;
+        }
+        trees
+    };
+    assert_same_distribution(&sample(RngVersion::V1), &sample(RngVersion::V2));
+}
+
+#[test]
+fn random_reconnect_v1_shuffles_all_edges() {
+    let g = StaticGraph::new_complete(5);
+    let edge = |u, v| g.edge_by_ends(u, v);
+    let forest = vec![edge(0, 1), edge(3, 4)];
+    let exclude = vec![edge(1, 2), edge(2, 3)];
+
+    // the version 1 is a Kruskal on all edges shuffled in each reconnection
+    let mut reconnect = RandomReconnect::new(&g);
+    let mut rng = Seed::from(3).new_rng(RngVersion::V1, Stream::Perturbation);
+    let mut expected_rng = Seed::from(3).new_rng(RngVersion::V1, Stream::Perturbation);
+    let mut edges = g.edges().collect::<Vec<_>>();
+    for _ in 0..10 {
+        let mut tree = forest.clone();
+        reconnect.reconnect(&mut tree, &exclude, &mut rng);
+        expected_rng.shuffle(&mut edges);
+        let not_excluded = edges.iter().filter(|e| !exclude.contains(e));
+        let expected = g.kruskal().edges(forest.iter().chain(not_excluded));
+        assert_eq!(expected.into_iter().collect::<Vec<_>>(), tree);
+    }
+}
+
+// Chi-squared test of homogeneity of two samples with the same size
+fn assert_same_distribution(
+    expected: &HashMap<Vec<usize>, u32>,
+    actual: &HashMap<Vec<usize>, u32>,
+) {
+    let keys = expected.keys().chain(actual.keys()).collect::<HashSet<_>>();
+    let stat: f64 = keys
+        .iter()
+        .map(|k| {
+            let a = f64::from(*expected.get(*k).unwrap_or(&0));
+            let b = f64::from(*actual.get(*k).unwrap_or(&0));
+            (a - b) * (a - b) / (a + b)
+        })
+        .sum();
+    let df = (keys.len() - 1) as f64;
+    // far in the tail of the chi-squared distribution
+    assert!(stat < df + 6.0 * (2.0 * df).sqrt(), "stat = {}", stat);
+}
+
+fn key<I: IntoIterator<Item = Edge<StaticGraph>>>(g: &StaticGraph, tree: I) -> Vec<usize> {
+    let index = g.edge_index();
+    let mut key = tree.into_iter().map(|e| index.get(e)).collect::<Vec<_>>();
+    key.sort();
+    key
+}
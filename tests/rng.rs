@@ -0,0 +1,207 @@
+extern crate fera;
+extern crate mstcc;
+
+mod common;
+
+use fera::graph::prelude::*;
+use fera::graph::sum_prop;
+use mstcc::*;
+
+// The values generated by a version must never change, if this test fails a new version must be
+// created instead
+
+#[test]
+fn streams_v1() {
+    let first = |stream| {
+        let mut rng = Seed::from(42).new_rng(RngVersion::V1, stream);
+        (0..4).map(|_| rng.next_u32()).collect::<Vec<_>>()
+    };
+    assert_eq!(
+        vec![3476582508, 1104694853, 1497153043, 1875889148],
+        first(Stream::Init)
+    );
+    assert_eq!(
+        vec![506049948, 1756597927, 4009362303, 157135905],
+        first(Stream::Perturbation)
+    );
+    assert_eq!(
+        vec![4253099399, 1146536798, 3857952853, 1113652845],
+        first(Stream::Generate)
+    );
+    assert_eq!(
+        vec![1172832471, 2797748058, 4205706287, 1911621497],
+        first(Stream::Tune)
+    );
+    assert_eq!(
+        vec![2338966422, 2143100109, 324860402, 214955974],
+        first(Stream::Worker(0))
+    );
+    assert_eq!(
+        vec![1975091927, 3688036587, 2824020893, 476398831],
+        first(Stream::Worker(1))
+    );
+
+    let mut rng = Seed::from(42).new_rng(RngVersion::V1, Stream::Init);
+    let indices = (0..10).map(|_| rng.gen_index(10)).collect::<Vec<_>>();
+    assert_eq!(vec![1, 6, 9, 4, 4, 7, 3, 7, 6, 9], indices);
+    let mut values = (0..10).collect::<Vec<_>>();
+    rng.shuffle(&mut values);
+    assert_eq!(vec![0, 3, 5, 4, 2, 7, 6, 1, 9, 8], values);
+
+    let expected = [
+        (
+            [
+                14931808174810353221,
+                6430223358667770876,
+                8125275122490189959,
+            ],
+            [734, 584, 217, 923, 517, 236, 159, 408],
+            [0, 9, 6, 7, 8, 1, 2, 5, 4, 3],
+        ),
+        (
+            [
+                2173467978559098535,
+                17220079969357378593,
+                10157802865596755628,
+            ],
+            [942, 936, 787, 629, 227, 401, 172, 637],
+            [5, 4, 0, 6, 9, 8, 7, 1, 2, 3],
+        ),
+        (
+            [
+                18266922826488791902,
+                16569781334258548333,
+                2228006769440711658,
+            ],
+            [504, 321, 305, 756, 379, 227, 935, 575],
+            [2, 3, 0, 1, 4, 8, 6, 9, 5, 7],
+        ),
+        (
+            [
+                5037277109429616474,
+                18063370961158211449,
+                11446022407962333455,
+            ],
+            [476, 943, 398, 681, 613, 358, 799, 847],
+            [8, 5, 0, 4, 6, 3, 1, 7, 2, 9],
+        ),
+        (
+            [
+                10045784291075235021,
+                1395264802570368966,
+                3601323776357822785,
+            ],
+            [28, 713, 151, 902, 747, 785, 272, 513],
+            [2, 4, 6, 5, 8, 9, 0, 3, 7, 1],
+        ),
+        (
+            [
+                8482955236746655979,
+                12129077379132114159,
+                11679703640720459159,
+            ],
+            [735, 676, 908, 840, 368, 198, 568, 84],
+            [1, 0, 2, 5, 9, 6, 7, 8, 4, 3],
+        ),
+    ];
+    for (&stream, expected) in STREAMS.iter().zip(&expected) {
+        assert_eq!(*expected, outputs(RngVersion::V1, stream), "{:?}", stream);
+    }
+}
+
+#[test]
+fn streams_v2() {
+    // the version 2 changes only the reconnection of the perturbations, not the streams
+    for &stream in &STREAMS {
+        assert_eq!(
+            outputs(RngVersion::V1, stream),
+            outputs(RngVersion::V2, stream),
+            "{:?}",
+            stream
+        );
+    }
+}
+
+const STREAMS: [Stream; 6] = [
+    Stream::Init,
+    Stream::Perturbation,
+    Stream::Generate,
+    Stream::Tune,
+    Stream::Worker(0),
+    Stream::Worker(1),
+];
+
+// The first values of next_u64, gen_index and shuffle of a stream of the seed 42
+fn outputs(version: RngVersion, stream: Stream) -> ([u64; 3], [usize; 8], [u32; 10]) {
+    let mut rng = Seed::from(42).new_rng(version, stream);
+    let mut u64s = [0; 3];
+    for x in &mut u64s {
+        *x = rng.next_u64();
+    }
+    let mut indices = [0; 8];
+    for i in &mut indices {
+        *i = rng.gen_index(1000);
+    }
+    let mut values = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+    rng.shuffle(&mut values);
+    (u64s, indices, values)
+}
+
+#[test]
+fn solution_v1() {
+    let (conflicts, weight, tree) = solve(RngVersion::V1, Seed::from(43));
+    assert_eq!(0, conflicts);
+    assert_eq!(861, weight);
+    assert_eq!(
+        "0-19 1-14 2-9 2-21 3-11 3-17 4-12 5-18 5-48 6-34 7-29 7-43 8-12 9-28 9-46 10-11 10-38 \
+         10-49 11-23 12-17 12-32 13-24 13-26 13-36 14-36 14-40 14-45 15-41 15-46 16-31 16-34 \
+         17-35 17-39 17-40 18-30 18-49 19-27 20-21 20-47 21-27 22-28 23-37 25-37 28-31 29-48 \
+         32-46 33-48 41-42 44-48",
+        tree
+    );
+    // the run is short, so the result depends on the seed
+    assert_ne!(tree, solve(RngVersion::V1, Seed::from(42)).2);
+}
+
+#[test]
+fn solution_v2() {
+    let (conflicts, weight, tree) = solve(RngVersion::V2, Seed::from(43));
+    assert_eq!(0, conflicts);
+    assert_eq!(834, weight);
+    assert_eq!(
+        "0-19 1-14 2-9 2-21 3-11 3-17 4-12 5-18 5-48 6-34 7-29 7-43 8-12 9-18 9-28 10-11 10-38 \
+         10-49 11-21 11-23 12-17 12-32 13-24 13-36 14-36 14-40 14-45 15-41 15-46 16-31 16-34 \
+         17-35 17-39 17-40 18-26 18-30 19-27 20-21 20-47 21-27 22-28 23-37 25-37 28-31 29-48 \
+         32-46 33-48 41-42 44-48",
+        tree
+    );
+    assert_ne!(tree, solve(RngVersion::V2, Seed::from(42)).2);
+}
+
+// Solves a fixed instance with a short ils-2ex starting from a random tree and returns the number
+// of conflicts, the weight and the sorted edges of the solution
+fn solve(version: RngVersion, seed: Seed) -> (u32, u32, String) {
+    let p = common::instance(Seed::from(42), 50, 200, 300);
+    p.beta.set(10_000);
+    let restart = common::random_restart(&p, seed.new_rng(version, Stream::Init));
+    let mut tree = vec![];
+    let mut ils = Ils::new(&p, restart, RandomExclusion::new(&p));
+    ils.max_iters = 10;
+    ils.num_excludes = 2;
+    ils.iters_restart = 10;
+    (ils.restart)(&mut tree);
+    let mut one = OneEdgeReplacement::new(&p);
+    let mut rng = seed.new_rng(version, Stream::Perturbation);
+    let conflicts = ils.run(&mut tree, &mut rng, |tree| one.run(tree));
+    let mut ends = tree
+        .iter()
+        .map(|&e| p.g.ends(e))
+        .map(|(u, v)| (u.min(v), u.max(v)))
+        .collect::<Vec<_>>();
+    ends.sort();
+    let ends = ends
+        .iter()
+        .map(|&(u, v)| format!("{}-{}", u, v))
+        .collect::<Vec<_>>();
+    (conflicts, sum_prop(&p.w, &tree), ends.join(" "))
+}
@@ -0,0 +1,80 @@
+extern crate fera;
+extern crate mstcc;
+
+mod common;
+
+use fera::graph::algs::Trees;
+use fera::graph::prelude::*;
+use mstcc::*;
+
+#[test]
+fn names() {
+    for &init in &Init::ALL {
+        assert_eq!(Ok(init), init.to_string().parse());
+    }
+    for &alg in &Algorithm::ALL {
+        assert_eq!(Ok(alg), alg.to_string().parse());
+    }
+    for &kind in &PerturbationKind::ALL {
+        assert_eq!(Ok(kind), kind.to_string().parse());
+    }
+    assert!("ils".parse::<Algorithm>().is_err());
+}
+
+#[test]
+fn solution() {
+    let p = common::instance(Seed::from(31), 30, 80, 150);
+    for &alg in &Algorithm::ALL {
+        let config = SolverConfig {
+            seed: Seed::from(1),
+            alg,
+            ils_max_iters: Some(20),
+            grasp_iters: 5,
+            ..SolverConfig::default()
+        };
+        let solution = solve(&p, &config);
+        assert!(p.g.spanning_subgraph(&solution.tree).is_tree());
+        let conflicts = TrackConflicts::with_edges(&p, &solution.tree);
+        assert_eq!(conflicts.total(), solution.num_conflicts);
+        assert_eq!(conflicts.pairs(), solution.conflicting_pairs);
+        assert_eq!(solution.num_conflicts == 0, solution.is_feasible());
+        assert_eq!(p.weight(&solution.tree), solution.weight);
+        assert_eq!(
+            solution.weight + 10_000 * solution.num_conflicts,
+            solution.obj
+        );
+        assert!(!solution.exact);
+
+        // the same seed gives the same solution
+        assert_eq!(solution.tree, solve(&p, &config).tree);
+    }
+}
+
+#[test]
+fn initial_and_exact() {
+    let p = common::instance(Seed::from(8), 20, 60, 0);
+
+    // without conflicts the exact algorithm finds a minimum spanning tree
+    let config = SolverConfig {
+        seed: Seed::from(8),
+        ..SolverConfig::default()
+    };
+    let solution = solve(&p, &config);
+    assert!(solution.exact);
+    assert_eq!(p.weight(p.mst()), solution.weight);
+
+    // a local search from an initial tree can only improve it
+    let mut rng = Seed::from(8).new_rng(RngVersion::V1, Stream::Init);
+    let initial = common::random_tree(&p, &mut rng);
+    // the exact algorithms are not used with an initial tree
+    let config = SolverConfig {
+        seed: Seed::from(8),
+        alg: Algorithm::TwoEx,
+        initial: Some(initial.clone()),
+        ..SolverConfig::default()
+    };
+    let solution = solve(&p, &config);
+    assert!(!solution.exact);
+    assert!(solution.weight <= p.weight(&initial));
+    assert_eq!(p.weight(p.mst()), solution.weight);
+}
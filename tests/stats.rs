@@ -0,0 +1,69 @@
+extern crate fera;
+extern crate mstcc;
+
+use fera::graph::prelude::*;
+use mstcc::{InstanceStats, MstCcProblem};
+
+#[test]
+fn stats() {
+    let (mut p, e) = new();
+    add_conflict(&mut p, e[3], e[4]);
+
+    let stats = InstanceStats::new(&p);
+    assert_eq!(6, stats.n);
+    assert_eq!(6, stats.m);
+    assert_eq!(1, stats.num_cc);
+    assert_eq!(vec![4, 2], stats.conflict_degrees);
+    assert_eq!(0.4, stats.density);
+    assert_eq!(1.0 / 15.0, stats.conflict_density);
+    assert_eq!(3, stats.num_bridges);
+    // mst: e1, e2, e3, e4, e5
+    assert_eq!(17, stats.mst_weight);
+    assert_eq!(1, stats.mst_conflicts);
+    assert_eq!(1, stats.conflict_components);
+    assert!(stats.conflict_bipartite);
+
+    add_conflict(&mut p, e[0], e[1]);
+    add_conflict(&mut p, e[1], e[2]);
+    let stats = InstanceStats::new(&p);
+    assert_eq!(vec![1, 4, 1], stats.conflict_degrees);
+    assert_eq!(2, stats.conflict_components);
+    assert!(stats.conflict_bipartite);
+
+    add_conflict(&mut p, e[0], e[2]);
+    let stats = InstanceStats::new(&p);
+    assert_eq!(4, stats.num_cc);
+    assert_eq!(vec![1, 2, 3], stats.conflict_degrees);
+    assert_eq!(2, stats.conflict_components);
+    assert!(!stats.conflict_bipartite);
+}
+
+fn add_conflict(p: &mut MstCcProblem, e: Edge<StaticGraph>, f: Edge<StaticGraph>) {
+    p.cc[e].push(f);
+    p.cc[f].push(e);
+    p.num_cc += 1;
+}
+
+fn new() -> (MstCcProblem, Vec<Edge<StaticGraph>>) {
+    // a triangle 0-1-2 connected to the path 2-3-4-5
+    let mut b = <StaticGraph as WithBuilder>::Builder::new(6, 6);
+    b.add_edge(0, 1); // e0
+    b.add_edge(1, 2); // e1
+    b.add_edge(0, 2); // e2
+    b.add_edge(2, 3); // e3
+    b.add_edge(3, 4); // e4
+    b.add_edge(4, 5); // e5
+    let (g, _, e) = b.finalize_();
+    let cc = g.edge_prop(vec![]);
+    let w = g.edge_prop_from_fn(|e| [3, 1, 2, 1, 4, 9][g.edge_index().get(e)]);
+    let p = MstCcProblem {
+        name: "test".to_owned(),
+        g,
+        w,
+        cc,
+        num_cc: 0,
+        alpha: 1.into(),
+        beta: 0.into(),
+    };
+    (p, e)
+}
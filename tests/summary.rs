@@ -0,0 +1,85 @@
+extern crate mstcc;
+
+use std::cmp::Ordering;
+
+use mstcc::{natural_cmp, summarize, RunResult};
+
+#[test]
+fn natural_order() {
+    assert_eq!(Ordering::Less, natural_cmp("z50-200-199", "z100-300-448"));
+    assert_eq!(Ordering::Less, natural_cmp("z100-300-448", "z100-300-897"));
+    assert_eq!(Ordering::Less, natural_cmp("z100-300", "z100-300-448"));
+    assert_eq!(Ordering::Less, natural_cmp("a9", "a010"));
+    assert_eq!(Ordering::Greater, natural_cmp("b1", "a2"));
+    assert_eq!(Ordering::Equal, natural_cmp("z50", "z050"));
+}
+
+#[test]
+fn summary() {
+    let run = |name: &str, time, conflicts, weight| RunResult {
+        config: String::new(),
+        name: name.into(),
+        time,
+        conflicts,
+        weight,
+    };
+    let results = vec![
+        run("z100-300-448", 1.0, 0, 10.0),
+        run("z50-200-199", 0.5, 1, 20.0),
+        run("z100-300-448", 3.0, 0, 12.0),
+        run("z100-300-448", 2.0, 1, 10.0),
+        run("z100-300-448", 6.0, 2, 16.0),
+    ];
+
+    let summaries = summarize(&results);
+    assert_eq!(2, summaries.len());
+
+    let s = &summaries[0];
+    assert_eq!("z50-200-199", s.name);
+    assert_eq!(1, s.samples);
+    assert_eq!(20.0, s.median_weight);
+    assert_eq!(0.0, s.std_weight);
+    assert_eq!(0.0, s.success_rate);
+
+    let s = &summaries[1];
+    assert_eq!("z100-300-448", s.name);
+    assert_eq!(4, s.samples);
+    assert_eq!(11.0, s.median_weight);
+    assert_eq!(10.0, s.best_weight);
+    assert_eq!(2, s.best_weight_freq);
+    assert_eq!(6.0f64.sqrt(), s.std_weight);
+    assert_eq!(2.5, s.median_time);
+    assert_eq!(12.0, s.total_time);
+    assert_eq!(0, s.min_conflicts);
+    assert_eq!(2, s.min_conflicts_freq);
+    assert_eq!(0.5, s.success_rate);
+}
+
+#[test]
+fn summary_configs() {
+    let run = |config: &str, name: &str| RunResult {
+        config: config.into(),
+        name: name.into(),
+        time: 1.0,
+        conflicts: 0,
+        weight: 10.0,
+    };
+    let results = vec![
+        run("B2", "z100"),
+        run("B1", "z100"),
+        run("B2", "z50"),
+        run("B2", "z100"),
+    ];
+    let groups = summarize(&results)
+        .into_iter()
+        .map(|s| (s.config, s.name, s.samples))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            ("B2".to_string(), "z50".to_string(), 1),
+            ("B2".to_string(), "z100".to_string(), 2),
+            ("B1".to_string(), "z100".to_string(), 1),
+        ],
+        groups
+    );
+}
@@ -0,0 +1,68 @@
+extern crate mstcc;
+
+use mstcc::{sample_configurations, Json, Race, RngVersion, Seed, Stream};
+
+#[test]
+fn race_discards_worse() {
+    // candidate i has cost i + block noise, except 3 and 4 that are always the best
+    let mut race = Race::new(6);
+    for b in 0..20 {
+        let costs = race
+            .alive()
+            .iter()
+            .map(|&i| match i {
+                3 | 4 => (b % 2) as u32,
+                _ => 10 + i as u32 + (b % 3) as u32,
+            })
+            .collect();
+        race.add_block(costs);
+    }
+    let mut alive = race.alive().to_vec();
+    alive.sort();
+    assert_eq!(vec![3, 4], alive);
+    assert_eq!(1.5, race.ranking()[0].1);
+}
+
+#[test]
+fn race_ties() {
+    let mut race = Race::new(4);
+    for _ in 0..20 {
+        race.add_block(vec![1, 1, 1, 1]);
+    }
+    assert_eq!(&[0, 1, 2, 3], race.alive());
+}
+
+#[test]
+fn race_first_test() {
+    let mut race = Race::new(3);
+    race.first_test = 10;
+    for _ in 0..9 {
+        assert!(race.add_block(vec![1, 2, 3]).is_empty());
+    }
+    assert_eq!(vec![1, 2], race.add_block(vec![1, 2, 3]));
+    assert_eq!(0, race.best());
+}
+
+#[test]
+fn configurations() {
+    let params = vec![
+        (
+            "a".to_string(),
+            vec![Json::Int(1), Json::Int(2), Json::Int(3)],
+        ),
+        ("b".to_string(), vec![Json::Bool(true), Json::Bool(false)]),
+    ];
+    let mut rng = Seed::from(0).new_rng(RngVersion::LATEST, Stream::Tune);
+
+    let mut all = sample_configurations(&params, 6, &mut rng);
+    assert_eq!(6, all.len());
+    all.sort_by_key(|c| format!("{:?}", c));
+    all.dedup();
+    assert_eq!(6, all.len());
+
+    let mut some = sample_configurations(&params, 4, &mut rng);
+    some.sort_by_key(|c| format!("{:?}", c));
+    some.dedup();
+    assert_eq!(4, some.len());
+    assert!(some.iter().all(|c| all.contains(c)));
+}
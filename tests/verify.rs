@@ -0,0 +1,85 @@
+extern crate fera;
+extern crate mstcc;
+
+use fera::graph::prelude::*;
+use mstcc::{verify, MstCcProblem, Violation};
+
+#[test]
+fn feasible() {
+    let p = new();
+    let v = verify(&p, &[(1, 2), (2, 3), (0, 3)]);
+    assert!(v.is_feasible());
+    assert_eq!(7, v.weight);
+    assert_eq!(0, v.num_conflicts);
+    assert_eq!(3, v.tree.len());
+}
+
+#[test]
+fn conflicts() {
+    let p = new();
+    let v = verify(&p, &[(2, 1), (0, 1), (2, 3)]);
+    assert!(!v.is_feasible());
+    assert_eq!(6, v.weight);
+    assert_eq!(1, v.num_conflicts);
+    assert_eq!(vec![Violation::Conflict((0, 1), (1, 2))], v.violations);
+}
+
+#[test]
+fn not_spanning_tree() {
+    let p = new();
+
+    let v = verify(&p, &[(0, 1), (1, 2), (0, 2)]);
+    assert_eq!(
+        vec![
+            Violation::NotSpanningTree,
+            Violation::Conflict((0, 1), (1, 2)),
+        ],
+        v.violations
+    );
+
+    let v = verify(&p, &[(0, 2), (0, 2), (0, 3)]);
+    assert_eq!(
+        vec![Violation::DuplicatedEdge(0, 2), Violation::NotSpanningTree],
+        v.violations
+    );
+
+    let v = verify(&p, &[(0, 1), (1, 4), (1, 1)]);
+    assert_eq!(
+        vec![
+            Violation::InvalidEdge(1, 4),
+            Violation::InvalidEdge(1, 1),
+            Violation::NotSpanningTree,
+        ],
+        v.violations
+    );
+}
+
+fn new() -> MstCcProblem {
+    let mut b = <StaticGraph as WithBuilder>::Builder::new(4, 6);
+    b.add_edge(0, 1); // e0
+    b.add_edge(0, 2); // e1
+    b.add_edge(0, 3); // e2
+    b.add_edge(1, 2); // e3
+    b.add_edge(1, 3); // e4
+    b.add_edge(2, 3); // e5
+    let (g, _, e) = b.finalize_();
+    let cc = g.edge_prop(vec![]);
+    let w = g.edge_prop_from_fn(|e| [3, 1, 4, 2, 5, 1][g.edge_index().get(e)]);
+    let mut p = MstCcProblem {
+        name: "test".to_owned(),
+        g,
+        w,
+        cc,
+        num_cc: 2,
+        alpha: 1.into(),
+        beta: 0.into(),
+    };
+
+    p.cc[e[1]].push(e[5]);
+    p.cc[e[5]].push(e[1]);
+
+    p.cc[e[0]].push(e[3]);
+    p.cc[e[3]].push(e[0]);
+
+    p
+}
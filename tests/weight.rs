@@ -0,0 +1,61 @@
+extern crate fera;
+extern crate mstcc;
+
+mod common;
+
+use mstcc::*;
+
+#[test]
+fn improves() {
+    assert!(1u32.improves(2));
+    assert!(!2u32.improves(2));
+    assert!((-1i64).improves(0));
+    assert!(1.0.improves(1.5));
+    assert!(!1.0.improves(1.0 + 1e-12));
+    assert!(!(0.1 + 0.2).improves(0.3));
+    assert!(!0.3.improves(0.1 + 0.2));
+    assert!(1e20.improves(f64::max_value()));
+    assert!(!f64::NAN.is_valid());
+    assert!(!f64::INFINITY.is_valid());
+}
+
+// Adding the same value to all the weights does not change the exchanges, so the local searches
+// must find the same trees with the integer weights and with the fractional weights
+#[test]
+fn fractional_weights() {
+    let seed = Seed::from(5);
+    let new = || {
+        let mut rng = seed.new_rng(RngVersion::V1, Stream::Generate);
+        new_random_instance("z40-120-80", 40, 120, 80, (1, 20), &mut rng)
+    };
+    let p = new();
+    let q = new().map_weights(|w| f64::from(w) + 0.1);
+    for &(a, b) in &[(1, 0), (1, 100)] {
+        p.alpha.set(a);
+        p.beta.set(b);
+        q.alpha.set(a);
+        q.beta.set(b);
+        let mut rng = seed.new_rng(RngVersion::V1, Stream::Init);
+        for &sort in &[false, true] {
+            let initial = common::random_tree(&p, &mut rng);
+
+            let mut one = OneEdgeReplacement::new(&p);
+            let mut one_f = OneEdgeReplacement::new(&q);
+            one.sort = sort;
+            one_f.sort = sort;
+            let (mut t1, mut t2) = (initial.clone(), initial.clone());
+            assert_eq!(one.run(&mut t1), one_f.run(&mut t2));
+            assert_eq!(t1, t2);
+
+            let mut two = TwoEdgeReplacement::new(&p);
+            let mut two_f = TwoEdgeReplacement::new(&q);
+            two.sort = sort;
+            two_f.sort = sort;
+            let (mut t1, mut t2) = (initial.clone(), initial);
+            assert_eq!(two.run(&mut t1), two_f.run(&mut t2));
+            assert_eq!(t1, t2);
+            let diff = q.weight(&t2) - f64::from(p.weight(&t1)) - 0.1 * 39.0;
+            assert!(diff.abs() < 1e-9);
+        }
+    }
+}